@@ -1,4 +1,8 @@
 use futures::{IntoFuture, Future};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use super::condition::RetryContext;
 
 pub trait Action {
     type Item;
@@ -6,6 +10,25 @@ pub trait Action {
     type Future: Future<Item=Self::Item, Error=Self::Error>;
 
     fn run(&mut self) -> Self::Future;
+
+    /// Runs a retry of the action, given the error of the previous attempt.
+    ///
+    /// This defaults to `run`, ignoring the error.
+    fn run_after(&mut self, _previous: &Self::Error) -> Self::Future {
+        self.run()
+    }
+
+    /// Runs an attempt as part of a retry sequence, given the error of the previous attempt,
+    /// if any, and the [`RetryContext`](condition/struct.RetryContext.html) of the attempt.
+    ///
+    /// This is how `RetryFuture` runs its attempts, and defaults to `run_after` or `run`,
+    /// ignoring the context. Adapters wrapping another action should forward it.
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, _context: &RetryContext) -> Self::Future {
+        match previous {
+            Some(previous) => self.run_after(previous),
+            None => self.run()
+        }
+    }
 }
 
 impl<T: IntoFuture, F: FnMut() -> T> Action for F {
@@ -17,3 +40,120 @@ impl<T: IntoFuture, F: FnMut() -> T> Action for F {
         self().into_future()
     }
 }
+
+/// An action driven by a closure which receives the [`RetryContext`](condition/struct.RetryContext.html)
+/// of each attempt, i.e. its number and the time elapsed since the initial one.
+///
+/// This allows e.g. adding an `x-attempt` header, or switching to a cheaper code path
+/// on later attempts.
+///
+/// The context is the one of the retry sequence running the action, so that it agrees with
+/// the one passed to conditions. Runs outside of a retry sequence, i.e. via `run`, are passed
+/// the context of an initial attempt.
+pub struct ActionWithContext<F> {
+    f: F
+}
+
+impl<F> ActionWithContext<F> {
+    /// Wraps the given closure.
+    pub fn new(f: F) -> ActionWithContext<F> {
+        ActionWithContext{f}
+    }
+}
+
+impl<T: IntoFuture, F: FnMut(&RetryContext) -> T> Action for ActionWithContext<F> {
+    type Item = T::Item;
+    type Error = T::Error;
+    type Future = T::Future;
+
+    fn run(&mut self) -> Self::Future {
+        (self.f)(&RetryContext::new(1, Duration::ZERO)).into_future()
+    }
+
+    fn run_with_context(&mut self, _previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        (self.f)(context).into_future()
+    }
+}
+
+/// An action driven by a closure which receives the error of the previous attempt, if any.
+///
+/// This `struct` is created by `RetryFuture::spawn_with_previous_error`.
+pub struct WithPreviousError<F, E> {
+    f: F,
+    error: PhantomData<fn(&E)>
+}
+
+impl<F, E> WithPreviousError<F, E> {
+    pub(crate) fn new(f: F) -> WithPreviousError<F, E> {
+        WithPreviousError{f, error: PhantomData}
+    }
+}
+
+impl<E, T: IntoFuture<Error=E>, F: FnMut(Option<&E>) -> T> Action for WithPreviousError<F, E> {
+    type Item = T::Item;
+    type Error = T::Error;
+    type Future = T::Future;
+
+    fn run(&mut self) -> Self::Future {
+        (self.f)(None).into_future()
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        (self.f)(Some(previous)).into_future()
+    }
+}
+
+/// An action which fails over between a list of targets, e.g. the replicas of a service,
+/// handing the next one to the closure on each attempt.
+///
+/// Attempts rotate through the targets in order, wrapping around after the last one,
+/// so that retries spread across them instead of hammering the same unavailable host.
+pub struct FailoverAction<T, F> {
+    targets: Vec<T>,
+    next: usize,
+    f: F
+}
+
+impl<T, F> FailoverAction<T, F> {
+    /// Wraps the given closure, starting with the first of the given targets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `targets` is empty.
+    pub fn new(targets: Vec<T>, f: F) -> FailoverAction<T, F> {
+        assert!(!targets.is_empty(), "FailoverAction requires at least one target");
+        FailoverAction{targets, next: 0, f}
+    }
+
+    /// Returns the targets, in the order they are attempted.
+    pub fn targets(&self) -> &[T] {
+        &self.targets
+    }
+}
+
+impl<T, R: IntoFuture, F: FnMut(&T) -> R> Action for FailoverAction<T, F> {
+    type Item = R::Item;
+    type Error = R::Error;
+    type Future = R::Future;
+
+    fn run(&mut self) -> Self::Future {
+        let target = &self.targets[self.next];
+        self.next = (self.next + 1) % self.targets.len();
+        (self.f)(target).into_future()
+    }
+}
+
+#[test]
+fn passes_the_context_to_the_closure() {
+    let mut action = ActionWithContext::new(|context: &RetryContext| Ok::<_, ()>(context.attempt()));
+    assert_eq!(action.run().wait(), Ok(1));
+    assert_eq!(action.run_with_context(Some(&()), &RetryContext::new(3, Duration::from_secs(1))).wait(), Ok(3));
+}
+
+#[test]
+fn rotates_through_the_targets() {
+    let mut action = FailoverAction::new(vec!["a", "b"], |target: &&str| Ok::<_, ()>(target.to_string()));
+    assert_eq!(action.run().wait(), Ok("a".to_string()));
+    assert_eq!(action.run().wait(), Ok("b".to_string()));
+    assert_eq!(action.run().wait(), Ok("a".to_string()));
+}
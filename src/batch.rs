@@ -0,0 +1,161 @@
+use futures::{Async, Future, Poll};
+use std::convert::Infallible;
+use std::mem;
+use std::time::{Duration, Instant};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryError, RetryFuture, RetryStats, Sleep};
+use super::strategy::{BackoffStrategy, IntoStrategy};
+
+/// Aggregate statistics about the retry sequences of a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BatchStats {
+    succeeded: usize,
+    failed: usize,
+    attempts: usize,
+    total_delay: Duration,
+    total_elapsed: Duration
+}
+
+impl BatchStats {
+    /// Returns the number of actions which eventually succeeded.
+    pub fn succeeded(&self) -> usize {
+        self.succeeded
+    }
+
+    /// Returns the number of actions which failed, despite being retried.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Returns the number of attempts made across all actions.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Returns the total time slept between attempts, summed across all actions.
+    pub fn total_delay(&self) -> Duration {
+        self.total_delay
+    }
+
+    /// Returns the time it took for the whole batch to complete.
+    pub fn total_elapsed(&self) -> Duration {
+        self.total_elapsed
+    }
+
+    fn add(&mut self, succeeded: bool, stats: &RetryStats) {
+        if succeeded {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.attempts += stats.attempts();
+        self.total_delay += stats.total_delay();
+    }
+}
+
+/// The results of a batch, in the order of its actions, along with aggregate statistics.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BatchOutcome<T, E> {
+    /// The result of each action.
+    pub results: Vec<Result<T, E>>,
+    /// Statistics about the attempts made.
+    pub stats: BatchStats
+}
+
+/// The result of a single action of a batch.
+type BatchResult<S, A> = Result<<A as Action>::Item, RetryError<<A as Action>::Error, <<S as Sleep>::Future as Future>::Error>>;
+
+/// Future that retries a batch of actions independently of each other, with a shared policy.
+///
+/// This `struct` is created by the [`retry_all`](fn.retry_all.html) function.
+pub struct RetryAll<S: Sleep, I: BackoffStrategy<A::Error>, A: Action> {
+    pending: Vec<Option<RetryFuture<S, I, A>>>,
+    results: Vec<Option<BatchResult<S, A>>>,
+    remaining: usize,
+    stats: BatchStats,
+    started: Option<Instant>
+}
+
+/// Retries each of the given actions independently, and concurrently, with a clone of the given
+/// strategy each, resolving with all of their results once every action has completed.
+///
+/// Actions depending on a key, e.g. fetching a list of URLs, can be created from the keys
+/// with an iterator adapter such as `map`.
+pub fn retry_all<S, T, I, It>(sleep: S, strategy: T, actions: It) -> RetryAll<S, I, It::Item>
+    where S: Sleep + Clone, T: IntoStrategy<Strategy=I>, It: IntoIterator, It::Item: Action, I: BackoffStrategy<<It::Item as Action>::Error> + Clone {
+    let strategy = strategy.into_strategy();
+    let pending: Vec<_> = actions.into_iter()
+        .map(|action| Some(RetryFuture::new(sleep.clone(), strategy.clone(), action)))
+        .collect();
+    RetryAll{
+        results: pending.iter().map(|_| None).collect(),
+        remaining: pending.len(),
+        pending,
+        stats: BatchStats{succeeded: 0, failed: 0, attempts: 0, total_delay: Duration::default(), total_elapsed: Duration::default()},
+        started: None
+    }
+}
+
+impl<S: Sleep, I: BackoffStrategy<A::Error>, A: Action> Future for RetryAll<S, I, A> {
+    type Item = BatchOutcome<A::Item, RetryError<A::Error, <S::Future as Future>::Error>>;
+    type Error = Infallible;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        for (pending, result) in self.pending.iter_mut().zip(self.results.iter_mut()) {
+            let polled = match *pending {
+                Some(ref mut future) => future.poll(),
+                None => continue
+            };
+            let completed = match polled {
+                Ok(Async::NotReady) => continue,
+                Ok(Async::Ready(item)) => Ok(item),
+                Err(err) => Err(err)
+            };
+            if let Some(future) = pending.take() {
+                self.stats.add(completed.is_ok(), &future.stats());
+            }
+            *result = Some(completed);
+            self.remaining -= 1;
+        }
+
+        if self.remaining > 0 {
+            return Ok(Async::NotReady);
+        }
+        self.stats.total_elapsed = started.elapsed();
+        Ok(Async::Ready(BatchOutcome{
+            results: mem::take(&mut self.results).into_iter().flatten().collect(),
+            stats: self.stats.clone()
+        }))
+    }
+}
+
+#[test]
+fn retries_each_action_independently() {
+    use tokio_timer::Timer;
+    use super::strategy::NoDelay;
+
+    let actions = (0..3).map(|key| {
+        let mut attempts = 0;
+        move || {
+            attempts += 1;
+            match key {
+                0 => Ok(key),
+                1 if attempts > 1 => Ok(key),
+                _ => Err(key)
+            }
+        }
+    });
+    let outcome = retry_all(Timer::default(), NoDelay.take(2), actions).wait().unwrap();
+
+    assert_eq!(outcome.results[0], Ok(0));
+    assert_eq!(outcome.results[1], Ok(1));
+    assert_eq!(outcome.results[2].as_ref().err().and_then(RetryError::operation_error), Some(&2));
+    assert_eq!(outcome.stats.succeeded(), 2);
+    assert_eq!(outcome.stats.failed(), 1);
+    assert_eq!(outcome.stats.attempts(), 1 + 2 + 3);
+}
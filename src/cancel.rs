@@ -0,0 +1,122 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Represents the errors of a cancellable retry sequence.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CancelError<E> {
+    /// The retry sequence was cancelled.
+    Cancelled,
+    /// The retry sequence failed before it was cancelled.
+    Failed(E)
+}
+
+impl<E: fmt::Display> fmt::Display for CancelError<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CancelError::Cancelled => write!(formatter, "retry cancelled"),
+            CancelError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error> Error for CancelError<E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            CancelError::Cancelled => None,
+            CancelError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Future that aborts a retry sequence once a cancellation signal completes,
+/// interrupting an attempt or delay that is still in progress.
+///
+/// This `struct` is created by the `cancel_on` method on `RetryFuture`.
+pub struct Cancellable<F, C> {
+    inner: F,
+    signal: Option<C>
+}
+
+impl<F, C> Cancellable<F, C> {
+    pub(crate) fn new(inner: F, signal: C) -> Cancellable<F, C> {
+        Cancellable{inner, signal: Some(signal)}
+    }
+
+    /// Returns a reference to the cancellable future.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+}
+
+impl<F: Future, C: Future> Future for Cancellable<F, C> {
+    type Item = F::Item;
+    type Error = CancelError<F::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let signalled = match self.signal {
+            Some(ref mut signal) => signal.poll(),
+            None => Ok(Async::NotReady)
+        };
+        match signalled {
+            Ok(Async::NotReady) => {},
+            Ok(Async::Ready(_)) => return Err(CancelError::Cancelled),
+            // A signal which failed, e.g. a dropped `oneshot::Sender`, can no longer cancel.
+            Err(_) => self.signal = None
+        }
+
+        self.inner.poll().map_err(CancelError::Failed)
+    }
+}
+
+/// Future that stops a retry sequence once a shutdown signal completes,
+/// resolving to `None` in that case.
+///
+/// This `struct` is created by the `until` method on `RetryFuture`.
+pub struct UntilShutdown<F, S> {
+    inner: Cancellable<F, S>
+}
+
+impl<F, S> UntilShutdown<F, S> {
+    pub(crate) fn new(inner: F, shutdown: S) -> UntilShutdown<F, S> {
+        UntilShutdown{inner: Cancellable::new(inner, shutdown)}
+    }
+
+    /// Returns a reference to the future racing against the shutdown signal.
+    pub fn get_ref(&self) -> &F {
+        self.inner.get_ref()
+    }
+}
+
+impl<F: Future, S: Future> Future for UntilShutdown<F, S> {
+    type Item = Option<F::Item>;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(item)) => Ok(Async::Ready(Some(item))),
+            Err(CancelError::Cancelled) => Ok(Async::Ready(None)),
+            Err(CancelError::Failed(err)) => Err(err)
+        }
+    }
+}
+
+#[test]
+fn completes_unless_cancelled() {
+    use futures::future;
+    use futures::sync::oneshot;
+    let (sender, receiver) = oneshot::channel::<()>();
+    drop(sender);
+    assert_eq!(Cancellable::new(future::ok::<_, ()>(42), receiver).wait(), Ok(42));
+}
+
+#[test]
+fn resolves_to_none_on_shutdown() {
+    use futures::future;
+    assert_eq!(UntilShutdown::new(future::empty::<(), ()>(), future::ok::<_, ()>(())).wait(), Ok(None));
+    assert_eq!(UntilShutdown::new(future::ok::<_, ()>(42), future::empty::<(), ()>()).wait(), Ok(Some(42)));
+}
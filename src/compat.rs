@@ -0,0 +1,222 @@
+//! Bridges between futures 0.1 and `std` futures, for code bases migrating to `async`/`.await`.
+//!
+//! Every future of this crate, such as a `RetryFuture` or the `Deadline` and `Cancellable`
+//! wrappers around it, implements both traits, so that it can be `.await`ed directly or
+//! composed with futures 0.1 combinators, whichever kind of action is retried.
+//! Futures 0.1 actions and services are retried as usual, closures returning `std` futures
+//! via `RetryFuture::spawn_async`.
+//!
+//! For the remaining futures, [`IntoStd`](struct.IntoStd.html) makes a futures 0.1 future
+//! awaitable, and [`FromStd`](struct.FromStd.html) turns a `std` future into a futures 0.1
+//! future, e.g. to implement a `tokio_service::Service` using `async` blocks.
+use futures::{self, Async};
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use super::{Action, Cancellable, Deadline, Fallback, ReclaimAction, ReconnectLoop, Recover, RetryAll, RetryError, RetryRace, UntilShutdown, WithStats};
+use super::future::Sleep;
+use super::condition::Condition;
+use super::hook::RetryHook;
+use super::std_future::{poll_01, poll_std};
+use super::strategy::{BackoffStrategy, ResettableStrategy};
+
+/// A futures 0.1 future which can be `.await`ed.
+///
+/// This `struct` is created by the `into_std` method on [`FutureExt`](trait.FutureExt.html).
+#[derive(Debug)]
+pub struct IntoStd<F> {
+    inner: F
+}
+
+impl<F> IntoStd<F> {
+    /// Returns a reference to the wrapped future.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+
+    /// Consumes the wrapper, returning the wrapped future.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F> Unpin for IntoStd<F> {}
+
+impl<F: futures::Future> Future for IntoStd<F> {
+    type Output = Result<F::Item, F::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(&mut self.get_mut().inner, cx)
+    }
+}
+
+/// A `std` future which can be used as a futures 0.1 future.
+///
+/// This `struct` is created by the [`from_std`](fn.from_std.html) function.
+pub struct FromStd<F> {
+    inner: Pin<Box<F>>
+}
+
+impl<T, E, F: Future<Output=Result<T, E>>> futures::Future for FromStd<F> {
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> futures::Poll<T, E> {
+        match poll_std(self.inner.as_mut()) {
+            Async::Ready(Ok(item)) => Ok(Async::Ready(item)),
+            Async::Ready(Err(err)) => Err(err),
+            Async::NotReady => Ok(Async::NotReady)
+        }
+    }
+}
+
+/// Turns a `std` future resolving to a `Result` into a futures 0.1 future.
+pub fn from_std<F: Future>(future: F) -> FromStd<F> {
+    FromStd{inner: Box::pin(future)}
+}
+
+/// Extends futures 0.1 futures with a conversion into `std` futures.
+pub trait FutureExt: futures::Future + Sized {
+    /// Wraps the future, so that it can be `.await`ed.
+    fn into_std(self) -> IntoStd<Self> {
+        IntoStd{inner: self}
+    }
+}
+
+impl<F: futures::Future> FutureExt for F {}
+
+// None of the wrappers below is structurally pinned, as futures 0.1 may be moved in between polls.
+
+impl<F, C> Unpin for Cancellable<F, C> {}
+
+impl<F: futures::Future, C: futures::Future> Future for Cancellable<F, C> {
+    type Output = Result<F::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<F, S> Unpin for UntilShutdown<F, S> {}
+
+impl<F: futures::Future, S: futures::Future> Future for UntilShutdown<F, S> {
+    type Output = Result<Option<F::Item>, F::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<F, T> Unpin for Deadline<F, T> {}
+
+impl<F: futures::Future, T: futures::Future> Future for Deadline<F, T> {
+    type Output = Result<F::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<F: futures::Future, G: futures::IntoFuture> Unpin for Fallback<F, G> {}
+
+impl<F, G, OE, TE> Future for Fallback<F, G> where F: futures::Future<Error=RetryError<OE, TE>>, G: futures::IntoFuture<Item=F::Item> {
+    type Output = Result<F::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<F, R> Unpin for Recover<F, R> {}
+
+impl<F: futures::Future, R: FnOnce(F::Error) -> F::Item> Future for Recover<F, R> {
+    type Output = Result<F::Item, Infallible>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<S: Sleep, I, A: Action> Unpin for RetryRace<S, I, A> {}
+
+impl<S: Sleep, I: Iterator<Item=Duration>, A: Action> Future for RetryRace<S, I, A> {
+    type Output = Result<A::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<S: Sleep, I: BackoffStrategy<A::Error>, A: Action> Unpin for RetryAll<S, I, A> {}
+
+impl<S: Sleep, I: BackoffStrategy<A::Error>, A: Action> Future for RetryAll<S, I, A> {
+    type Output = Result<<Self as futures::Future>::Item, Infallible>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<S: Sleep, I, C: Action, F, U: futures::IntoFuture> Unpin for ReconnectLoop<S, I, C, F, U> {}
+
+impl<S, I, C, F, U> Future for ReconnectLoop<S, I, C, F, U>
+    where S: Sleep, I: BackoffStrategy<C::Error> + ResettableStrategy, C: Action, F: FnMut(C::Item) -> U, U: futures::IntoFuture<Error=C::Error> {
+    type Output = Result<U::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<S, I, A, C, H> Unpin for WithStats<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {}
+
+impl<S, I, A, C, H> Future for WithStats<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    type Output = Result<<Self as futures::Future>::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+impl<S, I, A, C, H> Unpin for ReclaimAction<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {}
+
+impl<S, I, A, C, H> Future for ReclaimAction<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    type Output = Result<<Self as futures::Future>::Item, <Self as futures::Future>::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+#[test]
+fn awaits_wrapped_retry_futures() {
+    use std::time::Duration;
+    use tokio_timer::Timer;
+    use super::RetryFuture;
+    use super::std_future::block_on;
+    use super::strategy::NoDelay;
+
+    let future = RetryFuture::spawn(Timer::default(), NoDelay.take(2), || Ok::<u64, ()>(42))
+        .with_deadline(Duration::from_secs(10));
+
+    assert_eq!(block_on(future), Ok(42));
+}
+
+#[test]
+fn awaits_futures_0_1() {
+    use futures::future::ok;
+    use super::std_future::block_on;
+
+    assert_eq!(block_on(ok::<u64, ()>(42).into_std()), Ok(42));
+}
+
+#[test]
+fn waits_for_std_futures() {
+    use futures::Future;
+    use std::future::ready;
+
+    assert_eq!(from_std(ready(Ok::<u64, ()>(42))).wait(), Ok(42));
+}
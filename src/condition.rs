@@ -0,0 +1,646 @@
+//! Conditions deciding whether a failed attempt should be retried.
+//!
+//! Any `FnMut(&E) -> bool` is a condition. Conditions which need to consult
+//! an asynchronous source can be created using [`async_condition`](fn.async_condition.html),
+//! and conditions depending on the attempt number or the elapsed time using
+//! [`with_context`](fn.with_context.html). Boxed errors can be inspected
+//! using [`retry_if_downcast`](fn.retry_if_downcast.html).
+//!
+//! Conditions can be combined using the methods of [`ConditionExt`](trait.ConditionExt.html).
+//! Decisions are awaited via [`decide`](fn.decide.html), or by the retry sequence itself.
+//!
+//! Errors which know whether they are worth retrying can implement
+//! [`RetryableError`](trait.RetryableError.html) instead, and be retried
+//! via `RetryFuture::spawn_transient`.
+
+use std::error::Error;
+use std::io;
+use std::marker::PhantomData;
+use std::time::Duration;
+use futures::{Async, Future, IntoFuture, Poll};
+use futures::future::{self, ok, FutureResult};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// The progress of a retry, as passed to conditions and to
+/// [`ActionWithContext`](../struct.ActionWithContext.html)s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RetryContext {
+    attempt: usize,
+    elapsed: Duration
+}
+
+impl RetryContext {
+    pub(crate) fn new(attempt: usize, elapsed: Duration) -> RetryContext {
+        RetryContext{attempt, elapsed}
+    }
+
+    /// Returns the number of the attempt, starting at 1 for the initial one.
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    /// Returns the time elapsed since the initial attempt was started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// A condition deciding whether to retry after an error of type `E`.
+pub trait Condition<E> {
+    /// The future resolving to the decision.
+    ///
+    /// Should the future fail, the error is not retried.
+    type Future: Future<Item=bool>;
+
+    /// Decides whether to retry after the given error.
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future;
+
+    /// Polls a decision of this condition, given the error and context it is made on.
+    ///
+    /// This is how retry sequences await decisions, and defaults to polling the future.
+    /// Combinators override it to consult further conditions only once they are needed,
+    /// and adapters wrapping another condition should forward it.
+    fn poll_decision(&mut self, decision: &mut Self::Future, _error: &E, _context: &RetryContext) -> Poll<bool, <Self::Future as Future>::Error> {
+        decision.poll()
+    }
+}
+
+impl<E, F: FnMut(&E) -> bool> Condition<E> for F {
+    type Future = FutureResult<bool, ()>;
+
+    fn should_retry(&mut self, error: &E, _: &RetryContext) -> Self::Future {
+        ok(self(error))
+    }
+}
+
+/// A condition which retries every error.
+///
+/// This is the condition used by `RetryFuture::spawn`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Always;
+
+impl<E> Condition<E> for Always {
+    type Future = FutureResult<bool, ()>;
+
+    fn should_retry(&mut self, _: &E, _: &RetryContext) -> Self::Future {
+        ok(true)
+    }
+}
+
+/// An error which can tell whether the operation that produced it is worth retrying.
+pub trait RetryableError {
+    /// Returns whether the error is transient, and the operation should be retried.
+    fn is_retryable(&self) -> bool;
+}
+
+/// Transient kinds of I/O errors, such as `ConnectionReset`, `TimedOut`,
+/// `Interrupted` or `WouldBlock`, are retryable. All other kinds,
+/// such as `PermissionDenied` or `NotFound`, are considered permanent.
+impl RetryableError for io::ErrorKind {
+    fn is_retryable(&self) -> bool {
+        matches!(*self,
+            io::ErrorKind::ConnectionRefused |
+            io::ErrorKind::ConnectionReset |
+            io::ErrorKind::ConnectionAborted |
+            io::ErrorKind::NotConnected |
+            io::ErrorKind::BrokenPipe |
+            io::ErrorKind::TimedOut |
+            io::ErrorKind::Interrupted |
+            io::ErrorKind::WouldBlock)
+    }
+}
+
+/// I/O errors are retryable depending on their [kind](https://doc.rust-lang.org/std/io/enum.ErrorKind.html).
+impl RetryableError for io::Error {
+    fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+/// A condition which retries errors reporting to be retryable.
+///
+/// This is the condition used by `RetryFuture::spawn_transient`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Retryable;
+
+impl<E: RetryableError> Condition<E> for Retryable {
+    type Future = FutureResult<bool, ()>;
+
+    fn should_retry(&mut self, error: &E, _: &RetryContext) -> Self::Future {
+        ok(error.is_retryable())
+    }
+}
+
+/// Creates a condition from a function returning a future of the decision.
+///
+/// The future is awaited between the failed attempt and the next one,
+/// which allows consulting e.g. a circuit breaker or a configuration watch.
+pub fn async_condition<E, F, R>(f: F) -> AsyncCondition<F> where F: FnMut(&E) -> R, R: IntoFuture<Item=bool> {
+    AsyncCondition{f}
+}
+
+/// A condition driven by a function returning a future.
+///
+/// This `struct` is created by the [`async_condition`](fn.async_condition.html) function.
+#[derive(Clone)]
+pub struct AsyncCondition<F> {
+    f: F
+}
+
+impl<E, F, R> Condition<E> for AsyncCondition<F> where F: FnMut(&E) -> R, R: IntoFuture<Item=bool> {
+    type Future = R::Future;
+
+    fn should_retry(&mut self, error: &E, _: &RetryContext) -> Self::Future {
+        (self.f)(error).into_future()
+    }
+}
+
+/// Creates a condition from a function which also receives the
+/// [`RetryContext`](struct.RetryContext.html) of the failed attempt.
+///
+/// This allows e.g. giving up on authentication errors sooner than on network errors.
+pub fn with_context<E, F>(f: F) -> WithContext<F> where F: FnMut(&E, &RetryContext) -> bool {
+    WithContext{f}
+}
+
+/// A condition driven by a function receiving the retry context.
+///
+/// This `struct` is created by the [`with_context`](fn.with_context.html) function.
+#[derive(Clone)]
+pub struct WithContext<F> {
+    f: F
+}
+
+impl<E, F> Condition<E> for WithContext<F> where F: FnMut(&E, &RetryContext) -> bool {
+    type Future = FutureResult<bool, ()>;
+
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future {
+        ok((self.f)(error, context))
+    }
+}
+
+/// A type-erased error which can be downcast to a concrete error type.
+///
+/// This is implemented for boxed errors, and for `anyhow::Error` with the `anyhow` feature.
+pub trait DowncastError {
+    /// Returns a reference to the underlying error if it is of type `T`.
+    fn downcast_error<T: Error + Send + Sync + 'static>(&self) -> Option<&T>;
+}
+
+impl DowncastError for Box<dyn Error> {
+    fn downcast_error<T: Error + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.downcast_ref()
+    }
+}
+
+impl DowncastError for Box<dyn Error + Send> {
+    fn downcast_error<T: Error + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.downcast_ref()
+    }
+}
+
+impl DowncastError for Box<dyn Error + Send + Sync> {
+    fn downcast_error<T: Error + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.downcast_ref()
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl DowncastError for ::anyhow::Error {
+    fn downcast_error<T: Error + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.downcast_ref()
+    }
+}
+
+/// Creates a condition which downcasts errors to `T` before consulting the predicate.
+///
+/// Errors of any other type are not retried.
+pub fn retry_if_downcast<T, F>(f: F) -> IfDowncast<T, F> where F: FnMut(&T) -> bool {
+    IfDowncast{f, marker: PhantomData}
+}
+
+/// A condition consulting a predicate on downcast errors.
+///
+/// This `struct` is created by the [`retry_if_downcast`](fn.retry_if_downcast.html) function.
+pub struct IfDowncast<T, F> {
+    f: F,
+    marker: PhantomData<fn(&T)>
+}
+
+impl<T, F: Clone> Clone for IfDowncast<T, F> {
+    fn clone(&self) -> IfDowncast<T, F> {
+        IfDowncast{f: self.f.clone(), marker: PhantomData}
+    }
+}
+
+impl<E, T, F> Condition<E> for IfDowncast<T, F> where E: DowncastError, T: Error + Send + Sync + 'static, F: FnMut(&T) -> bool {
+    type Future = FutureResult<bool, ()>;
+
+    fn should_retry(&mut self, error: &E, _: &RetryContext) -> Self::Future {
+        ok(error.downcast_error::<T>().is_some_and(|error| (self.f)(error)))
+    }
+}
+
+/// Awaits the decision of a condition on the given error, polling it via
+/// `Condition::poll_decision`.
+///
+/// This is how retry sequences await decisions, and allows awaiting the decisions of combined
+/// conditions outside of them, e.g. in tests.
+pub fn decide<'a, E, C: Condition<E>>(condition: &'a mut C, error: &'a E, context: RetryContext) -> Decide<'a, E, C> {
+    let decision = condition.should_retry(error, &context);
+    Decide{condition, error, context, decision}
+}
+
+/// Future of the decision of a condition.
+///
+/// This `struct` is created by the [`decide`](fn.decide.html) function.
+pub struct Decide<'a, E: 'a, C: Condition<E> + 'a> {
+    condition: &'a mut C,
+    error: &'a E,
+    context: RetryContext,
+    decision: C::Future
+}
+
+impl<'a, E, C: Condition<E>> Future for Decide<'a, E, C> {
+    type Item = bool;
+    type Error = <C::Future as Future>::Error;
+
+    fn poll(&mut self) -> Poll<bool, Self::Error> {
+        self.condition.poll_decision(&mut self.decision, self.error, &self.context)
+    }
+}
+
+/// The state of a combined decision: the first condition is awaited, and only then is the
+/// second one consulted, should the first one not have decided on its own.
+enum Combined<A, B> {
+    First(A),
+    Second(B)
+}
+
+/// Polls a combined decision, which is decided by the first condition alone if it yields
+/// `decisive`. Failures of either condition count as not retrying.
+fn poll_combined<E, A, B>(first: &mut A, second: &mut B, state: &mut Combined<A::Future, B::Future>, decisive: bool, error: &E, context: &RetryContext) -> Poll<bool, ()>
+    where A: Condition<E>, B: Condition<E> {
+    let retry = match *state {
+        Combined::First(ref mut decision) => match first.poll_decision(decision, error, context) {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(retry)) => retry,
+            Err(_) => false
+        },
+        Combined::Second(ref mut decision) => return Ok(second.poll_decision(decision, error, context).unwrap_or(Async::Ready(false)))
+    };
+    if retry == decisive {
+        return Ok(Async::Ready(retry));
+    }
+    *state = Combined::Second(second.should_retry(error, context));
+    poll_combined(first, second, state, decisive, error, context)
+}
+
+/// Polls a combined decision on its own, which only works until the second condition is needed.
+fn poll_alone<A, B>(state: &mut Combined<A, B>, decisive: bool) -> Poll<bool, ()>
+    where A: Future<Item=bool>, B: Future<Item=bool> {
+    let retry = match *state {
+        Combined::First(ref mut decision) => match decision.poll() {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(retry)) => retry,
+            Err(_) => false
+        },
+        Combined::Second(ref mut decision) => return Ok(decision.poll().unwrap_or(Async::Ready(false)))
+    };
+    assert!(retry == decisive, "the decision of a combined condition needs to be awaited via Condition::poll_decision");
+    Ok(Async::Ready(retry))
+}
+
+/// Extension methods for combining conditions.
+///
+/// A condition future failing counts as not retrying, also when combined.
+pub trait ConditionExt: Sized {
+    /// Retries only if both conditions hold.
+    ///
+    /// The second condition is only consulted once the first one held, so that stateful
+    /// conditions such as `stop_after_repeats` only observe the errors they decide on.
+    /// The decision is therefore awaited via `Condition::poll_decision`, see `decide`.
+    fn and<C>(self, other: C) -> And<Self, C> {
+        And{first: self, second: other}
+    }
+
+    /// Retries if either condition holds.
+    ///
+    /// The second condition is only consulted once the first one did not hold, see `and`.
+    fn or<C>(self, other: C) -> Or<Self, C> {
+        Or{first: self, second: other}
+    }
+
+    /// Retries only if the condition does not hold.
+    fn negate(self) -> Negate<Self> {
+        Negate{inner: self}
+    }
+
+    /// Stops retrying once the same error has occurred the given number of times in a row.
+    ///
+    /// Errors are compared by equality. Until then, the condition decides as usual.
+    fn stop_after_repeats<E: Clone + PartialEq>(self, times: usize) -> StopAfterRepeats<Self, fn(&E) -> E, E> {
+        self.stop_after_repeats_by(times, Clone::clone)
+    }
+
+    /// Like `stop_after_repeats`, but compares errors by the given key function.
+    fn stop_after_repeats_by<E, K: PartialEq, F: FnMut(&E) -> K>(self, times: usize, key: F) -> StopAfterRepeats<Self, F, K> {
+        StopAfterRepeats{inner: self, key, times, last: None, repeats: 0}
+    }
+}
+
+impl<T> ConditionExt for T {}
+
+/// A condition which holds if both of two conditions hold.
+///
+/// This `struct` is created by the [`and`](trait.ConditionExt.html#method.and)
+/// method on [`ConditionExt`](trait.ConditionExt.html).
+#[derive(Clone, Copy, Debug)]
+pub struct And<A, B> {
+    first: A,
+    second: B
+}
+
+impl<E, A: Condition<E>, B: Condition<E>> Condition<E> for And<A, B> {
+    type Future = AndFuture<A::Future, B::Future>;
+
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future {
+        AndFuture{state: Combined::First(self.first.should_retry(error, context))}
+    }
+
+    fn poll_decision(&mut self, decision: &mut Self::Future, error: &E, context: &RetryContext) -> Poll<bool, ()> {
+        poll_combined(&mut self.first, &mut self.second, &mut decision.state, false, error, context)
+    }
+}
+
+/// Future of an [`And`](struct.And.html) condition.
+///
+/// Polled on its own, rather than via `Condition::poll_decision`, it panics once the second
+/// condition needs to be consulted.
+pub struct AndFuture<A, B> {
+    state: Combined<A, B>
+}
+
+impl<A: Future<Item=bool>, B: Future<Item=bool>> Future for AndFuture<A, B> {
+    type Item = bool;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<bool, ()> {
+        poll_alone(&mut self.state, false)
+    }
+}
+
+/// A condition which holds if either of two conditions holds.
+///
+/// This `struct` is created by the [`or`](trait.ConditionExt.html#method.or)
+/// method on [`ConditionExt`](trait.ConditionExt.html).
+#[derive(Clone, Copy, Debug)]
+pub struct Or<A, B> {
+    first: A,
+    second: B
+}
+
+impl<E, A: Condition<E>, B: Condition<E>> Condition<E> for Or<A, B> {
+    type Future = OrFuture<A::Future, B::Future>;
+
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future {
+        OrFuture{state: Combined::First(self.first.should_retry(error, context))}
+    }
+
+    fn poll_decision(&mut self, decision: &mut Self::Future, error: &E, context: &RetryContext) -> Poll<bool, ()> {
+        poll_combined(&mut self.first, &mut self.second, &mut decision.state, true, error, context)
+    }
+}
+
+/// Future of an [`Or`](struct.Or.html) condition.
+///
+/// Polled on its own, rather than via `Condition::poll_decision`, it panics once the second
+/// condition needs to be consulted.
+pub struct OrFuture<A, B> {
+    state: Combined<A, B>
+}
+
+impl<A: Future<Item=bool>, B: Future<Item=bool>> Future for OrFuture<A, B> {
+    type Item = bool;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<bool, ()> {
+        poll_alone(&mut self.state, true)
+    }
+}
+
+/// A condition which holds if another condition does not.
+///
+/// This `struct` is created by the [`negate`](trait.ConditionExt.html#method.negate)
+/// method on [`ConditionExt`](trait.ConditionExt.html).
+#[derive(Clone, Copy, Debug)]
+pub struct Negate<C> {
+    inner: C
+}
+
+impl<E, C: Condition<E>> Condition<E> for Negate<C> {
+    type Future = NegateFuture<C::Future>;
+
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future {
+        NegateFuture{inner: self.inner.should_retry(error, context)}
+    }
+
+    fn poll_decision(&mut self, decision: &mut Self::Future, error: &E, context: &RetryContext) -> Poll<bool, <Self::Future as Future>::Error> {
+        self.inner.poll_decision(&mut decision.inner, error, context).map(|retry| retry.map(|retry| !retry))
+    }
+}
+
+/// Future of a [`Negate`](struct.Negate.html) condition.
+pub struct NegateFuture<F> {
+    inner: F
+}
+
+impl<F: Future<Item=bool>> Future for NegateFuture<F> {
+    type Item = bool;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<bool, F::Error> {
+        self.inner.poll().map(|retry| retry.map(|retry| !retry))
+    }
+}
+
+/// A condition which stops retrying once the same error occurs too often in a row.
+///
+/// This `struct` is created by the [`stop_after_repeats`](trait.ConditionExt.html#method.stop_after_repeats)
+/// and [`stop_after_repeats_by`](trait.ConditionExt.html#method.stop_after_repeats_by)
+/// methods on [`ConditionExt`](trait.ConditionExt.html).
+#[derive(Clone)]
+pub struct StopAfterRepeats<C, F, K> {
+    inner: C,
+    key: F,
+    times: usize,
+    last: Option<K>,
+    repeats: usize
+}
+
+impl<E, C, F, K> Condition<E> for StopAfterRepeats<C, F, K> where C: Condition<E>, F: FnMut(&E) -> K, K: PartialEq {
+    type Future = future::Either<FutureResult<bool, <C::Future as Future>::Error>, C::Future>;
+
+    fn should_retry(&mut self, error: &E, context: &RetryContext) -> Self::Future {
+        let key = (self.key)(error);
+
+        if self.last.as_ref() == Some(&key) {
+            self.repeats += 1;
+        } else {
+            self.last = Some(key);
+            self.repeats = 1;
+        }
+
+        if self.repeats >= self.times {
+            future::Either::A(ok(false))
+        } else {
+            future::Either::B(self.inner.should_retry(error, context))
+        }
+    }
+
+    fn poll_decision(&mut self, decision: &mut Self::Future, error: &E, context: &RetryContext) -> Poll<bool, <Self::Future as Future>::Error> {
+        match *decision {
+            future::Either::A(ref mut decision) => decision.poll(),
+            future::Either::B(ref mut decision) => self.inner.poll_decision(decision, error, context)
+        }
+    }
+}
+
+#[test]
+fn wraps_synchronous_predicates() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = |err: &u64| *err > 1;
+
+    assert_eq!(condition.should_retry(&2, &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&1, &context).wait(), Ok(false));
+}
+
+#[test]
+fn awaits_asynchronous_predicates() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = async_condition(|err: &u64| Ok::<bool, ()>(*err > 1));
+
+    assert_eq!(condition.should_retry(&2, &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&1, &context).wait(), Ok(false));
+}
+
+#[test]
+fn retries_transient_io_errors() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = Retryable;
+
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::ConnectionReset, "reset"), &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::TimedOut, "timed out"), &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::PermissionDenied, "denied"), &context).wait(), Ok(false));
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::NotFound, "not found"), &context).wait(), Ok(false));
+}
+
+#[test]
+fn passes_the_context_to_contextual_predicates() {
+    let mut condition = with_context(|err: &u64, context: &RetryContext| *err > 1 && context.attempt() < 3);
+
+    assert_eq!(condition.should_retry(&2, &RetryContext::new(2, Duration::ZERO)).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&2, &RetryContext::new(3, Duration::ZERO)).wait(), Ok(false));
+}
+
+#[test]
+fn retries_downcast_errors_matching_the_predicate() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = retry_if_downcast(|err: &io::Error| err.kind() == io::ErrorKind::TimedOut);
+
+    let timed_out: Box<dyn Error + Send + Sync> = Box::new(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+    let denied: Box<dyn Error + Send + Sync> = Box::new(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+    let other: Box<dyn Error + Send + Sync> = From::from("something else");
+
+    assert_eq!(condition.should_retry(&timed_out, &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&denied, &context).wait(), Ok(false));
+    assert_eq!(condition.should_retry(&other, &context).wait(), Ok(false));
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn downcasts_anyhow_errors() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = retry_if_downcast(|err: &io::Error| err.kind() == io::ErrorKind::TimedOut);
+    let timed_out = ::anyhow::Error::new(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+
+    assert_eq!(condition.should_retry(&timed_out, &context).wait(), Ok(true));
+}
+
+#[test]
+fn combines_conditions() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = Retryable
+        .or(|err: &io::Error| err.kind() == io::ErrorKind::NotFound)
+        .and((|err: &io::Error| err.kind() == io::ErrorKind::Interrupted).negate());
+
+    assert_eq!(decide(&mut condition, &io::Error::new(io::ErrorKind::TimedOut, "timed out"), context).wait(), Ok(true));
+    assert_eq!(decide(&mut condition, &io::Error::new(io::ErrorKind::NotFound, "not found"), context).wait(), Ok(true));
+    assert_eq!(decide(&mut condition, &io::Error::new(io::ErrorKind::Interrupted, "interrupted"), context).wait(), Ok(false));
+    assert_eq!(decide(&mut condition, &io::Error::new(io::ErrorKind::PermissionDenied, "denied"), context).wait(), Ok(false));
+}
+
+#[test]
+fn consults_the_second_condition_only_if_needed() {
+    use std::cell::Cell;
+    let context = RetryContext::new(1, Duration::ZERO);
+    let consulted = Cell::new(0);
+    let second = |_: &u64| {
+        consulted.set(consulted.get() + 1);
+        true
+    };
+
+    assert_eq!(decide(&mut (|err: &u64| *err > 1).and(second), &1, context).wait(), Ok(false));
+    assert_eq!(decide(&mut (|err: &u64| *err > 1).or(second), &2, context).wait(), Ok(true));
+    assert_eq!(consulted.get(), 0);
+
+    assert_eq!(decide(&mut (|err: &u64| *err > 1).and(second), &2, context).wait(), Ok(true));
+    assert_eq!(consulted.get(), 1);
+}
+
+#[test]
+fn awaits_the_first_condition_before_consulting_the_second() {
+    use futures::future::lazy;
+    use futures::sync::oneshot;
+    use std::cell::Cell;
+    let context = RetryContext::new(1, Duration::ZERO);
+    let (sender, receiver) = oneshot::channel();
+    let mut receiver = Some(receiver);
+    let consulted = Cell::new(0);
+    let mut condition = async_condition(|_: &u64| receiver.take().unwrap()).and(|_: &u64| {
+        consulted.set(consulted.get() + 1);
+        true
+    });
+
+    let mut decision = decide(&mut condition, &1, context);
+    assert_eq!(lazy(|| Ok::<_, ()>(decision.poll())).wait(), Ok(Ok(Async::NotReady)));
+    assert_eq!(consulted.get(), 0);
+
+    sender.send(false).unwrap();
+    assert_eq!(decision.wait(), Ok(false));
+    assert_eq!(consulted.get(), 0);
+}
+
+#[test]
+fn stops_after_repeated_identical_errors() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = Always.stop_after_repeats(3);
+
+    assert_eq!(condition.should_retry(&"dns", &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&"dns", &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&"reset", &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&"dns", &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&"dns", &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&"dns", &context).wait(), Ok(false));
+}
+
+#[test]
+fn compares_repeated_errors_by_key() {
+    let context = RetryContext::new(1, Duration::ZERO);
+    let mut condition = Always.stop_after_repeats_by(2, |err: &io::Error| err.kind());
+
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::TimedOut, "first"), &context).wait(), Ok(true));
+    assert_eq!(condition.should_retry(&io::Error::new(io::ErrorKind::TimedOut, "second"), &context).wait(), Ok(false));
+}
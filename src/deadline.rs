@@ -0,0 +1,84 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::expiry::Expiry;
+
+/// Represents the errors of a retry sequence bounded by a deadline.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum DeadlineError<E, TE> {
+    /// The retry sequence did not complete within the given deadline.
+    DeadlineExceeded(Duration),
+    /// Sleeping until the deadline failed, e.g. because the deadline exceeds the `max_timeout`
+    /// of the timer, so that the retry sequence was aborted rather than left unbounded.
+    TimerError(TE),
+    /// The retry sequence failed before the deadline.
+    Failed(E)
+}
+
+impl<E: fmt::Display, TE: fmt::Display> fmt::Display for DeadlineError<E, TE> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DeadlineError::DeadlineExceeded(deadline) => write!(formatter, "deadline of {:?} exceeded", deadline),
+            DeadlineError::TimerError(ref err) => write!(formatter, "sleeping until the deadline failed: {}", err),
+            DeadlineError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error, TE: Error> Error for DeadlineError<E, TE> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            DeadlineError::DeadlineExceeded(_) => None,
+            DeadlineError::TimerError(ref err) => Some(err),
+            DeadlineError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Future that aborts a retry sequence once a deadline has passed,
+/// including an attempt or delay that is still in progress.
+///
+/// This `struct` is created by the `with_deadline` method on `RetryFuture`.
+pub struct Deadline<F, T> {
+    inner: F,
+    timer: Expiry<T>,
+    deadline: Duration
+}
+
+impl<F, T> Deadline<F, T> {
+    pub(crate) fn new(inner: F, timer: T, deadline: Duration) -> Deadline<F, T> {
+        Deadline{inner, timer: Expiry::new(timer), deadline}
+    }
+
+    /// Returns a reference to the bounded future.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+}
+
+impl<F: Future, T: Future> Future for Deadline<F, T> {
+    type Item = F::Item;
+    type Error = DeadlineError<F::Error, T::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.timer.race(&mut self.inner, DeadlineError::Failed, DeadlineError::TimerError)? {
+            Async::Ready(Some(item)) => Ok(Async::Ready(item)),
+            Async::Ready(None) => Err(DeadlineError::DeadlineExceeded(self.deadline)),
+            Async::NotReady => Ok(Async::NotReady)
+        }
+    }
+}
+
+#[test]
+fn aborts_once_the_deadline_has_passed() {
+    use futures::future;
+    use tokio_timer::Timer;
+    let timer = Timer::default();
+    let res = Deadline::new(future::empty::<(), ()>(), timer.sleep(Duration::from_millis(10)), Duration::from_millis(10)).wait();
+    assert_eq!(res, Err(DeadlineError::DeadlineExceeded(Duration::from_millis(10))));
+}
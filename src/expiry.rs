@@ -0,0 +1,68 @@
+use futures::{Async, Future, Poll};
+
+/// A timer bounding something else, such as a single attempt or a whole retry sequence.
+///
+/// Errors of sleeping are passed up rather than swallowed, so that a broken timer, e.g. one
+/// refusing a sleep beyond its `max_timeout`, cannot quietly leave something unbounded.
+pub(crate) struct Expiry<T> {
+    timer: Option<T>
+}
+
+impl<T> Expiry<T> {
+    pub(crate) fn new(timer: T) -> Expiry<T> {
+        Expiry{timer: Some(timer)}
+    }
+
+    /// Constructs a timer which never expires.
+    pub(crate) fn never() -> Expiry<T> {
+        Expiry{timer: None}
+    }
+}
+
+impl<T: Future> Expiry<T> {
+    /// Returns whether the timer expired just now, which it does at most once,
+    /// or the error of sleeping, after which the timer never expires.
+    pub(crate) fn poll_expired(&mut self) -> Result<bool, T::Error> {
+        let expired = match self.timer {
+            Some(ref mut timer) => timer.poll(),
+            None => return Ok(false)
+        };
+        match expired {
+            Ok(Async::NotReady) => Ok(false),
+            Ok(Async::Ready(_)) => {
+                self.timer = None;
+                Ok(true)
+            },
+            Err(err) => {
+                self.timer = None;
+                Err(err)
+            }
+        }
+    }
+
+    /// Polls the given future, and then the timer, resolving with `None`
+    /// once the timer expired before the future completed.
+    ///
+    /// Errors of the future and of sleeping are converted via `failed` and `broken`.
+    pub(crate) fn race<F, E, M, N>(&mut self, inner: &mut F, failed: M, broken: N) -> Poll<Option<F::Item>, E>
+        where F: Future, M: FnOnce(F::Error) -> E, N: FnOnce(T::Error) -> E {
+        if let Async::Ready(item) = inner.poll().map_err(failed)? {
+            return Ok(Async::Ready(Some(item)));
+        }
+        if self.poll_expired().map_err(broken)? {
+            Ok(Async::Ready(None))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+#[test]
+fn passes_up_the_errors_of_sleeping() {
+    use futures::future::{empty, err, lazy, FutureResult};
+
+    let mut expiry: Expiry<FutureResult<(), &str>> = Expiry::new(err("too long"));
+    let polled = lazy(|| Ok::<_, ()>(expiry.race(&mut empty::<(), ()>(), |()| "failed", |err| err))).wait().unwrap();
+    assert_eq!(polled, Err("too long"));
+    assert_eq!(expiry.poll_expired(), Ok(false));
+}
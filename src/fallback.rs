@@ -0,0 +1,161 @@
+use futures::{Async, Future, IntoFuture, Poll};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::RetryError;
+
+/// Represents the errors of a retry sequence with a fallback.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum FallbackError<E, G> {
+    /// The primary retry sequence failed without exhausting its retries, e.g. with an error
+    /// which is not retried, so the fallback was not run.
+    Primary(E),
+    /// The primary retry sequence exhausted its retries, and the fallback failed as well.
+    Fallback {
+        /// The error of the primary retry sequence.
+        primary: E,
+        /// The error of the fallback.
+        fallback: G
+    }
+}
+
+impl<E: fmt::Display, G: fmt::Display> fmt::Display for FallbackError<E, G> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            FallbackError::Primary(ref primary) => primary.fmt(formatter),
+            FallbackError::Fallback{ref primary, ref fallback} => write!(formatter, "{} (fallback: {})", primary, fallback)
+        }
+    }
+}
+
+impl<E: Error, G: Error> Error for FallbackError<E, G> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            FallbackError::Primary(ref primary) => Some(primary),
+            FallbackError::Fallback{ref fallback, ..} => Some(fallback)
+        }
+    }
+}
+
+enum FallbackState<F: Future, G: IntoFuture> {
+    Primary(F, G),
+    Fallback(F::Error, G::Future),
+    Done
+}
+
+/// Future that runs a fallback, e.g. another retry sequence against a replica,
+/// once a retry sequence has exhausted its retries.
+///
+/// Other failures of the retry sequence, such as errors which are not retried, or retries
+/// rejected by a limiter, are reported via `FallbackError::Primary` without running the
+/// fallback, as it would most likely fail the same way.
+///
+/// This `struct` is created by the `or_else_try` method on `RetryFuture`.
+pub struct Fallback<F: Future, G: IntoFuture> {
+    state: FallbackState<F, G>
+}
+
+impl<F: Future, G: IntoFuture> Fallback<F, G> {
+    pub(crate) fn new(primary: F, fallback: G) -> Fallback<F, G> {
+        Fallback{state: FallbackState::Primary(primary, fallback)}
+    }
+
+    /// Returns whether the primary retry sequence has exhausted its retries, and the fallback is running.
+    pub fn is_falling_back(&self) -> bool {
+        matches!(self.state, FallbackState::Fallback(..))
+    }
+}
+
+impl<F, G, OE, TE> Future for Fallback<F, G> where F: Future<Error=RetryError<OE, TE>>, G: IntoFuture<Item=F::Item> {
+    type Item = F::Item;
+    type Error = FallbackError<F::Error, G::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            self.state = match mem::replace(&mut self.state, FallbackState::Done) {
+                FallbackState::Primary(mut primary, fallback) => match primary.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = FallbackState::Primary(primary, fallback);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(item)) => return Ok(Async::Ready(item)),
+                    Err(err @ RetryError::RetriesExhausted{..}) => FallbackState::Fallback(err, fallback.into_future()),
+                    Err(err) => return Err(FallbackError::Primary(err))
+                },
+                FallbackState::Fallback(primary, mut fallback) => match fallback.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = FallbackState::Fallback(primary, fallback);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(item)) => return Ok(Async::Ready(item)),
+                    Err(err) => return Err(FallbackError::Fallback{primary, fallback: err})
+                },
+                FallbackState::Done => panic!("polled Fallback after completion")
+            };
+        }
+    }
+}
+
+/// Future that resolves to a fallback value computed from the final error of a retry sequence,
+/// e.g. to serve a stale or default value once retries are exhausted.
+///
+/// This `struct` is created by the `unwrap_or_else_after_retries` method on `RetryFuture`.
+pub struct Recover<F, R> {
+    inner: F,
+    recover: Option<R>
+}
+
+impl<F, R> Recover<F, R> {
+    pub(crate) fn new(inner: F, recover: R) -> Recover<F, R> {
+        Recover{inner, recover: Some(recover)}
+    }
+
+    /// Returns a reference to the recovered future.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+}
+
+impl<F: Future, R: FnOnce(F::Error) -> F::Item> Future for Recover<F, R> {
+    type Item = F::Item;
+    type Error = Infallible;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll() {
+            Ok(ready) => Ok(ready),
+            Err(err) => {
+                let recover = self.recover.take().expect("polled Recover after completion");
+                Ok(Async::Ready(recover(err)))
+            }
+        }
+    }
+}
+
+#[test]
+fn falls_back_only_once_retries_are_exhausted() {
+    use futures::future::{err, ok};
+    use std::time::Duration;
+
+    let exhausted = || RetryError::RetriesExhausted::<u64, ()>{last_error: 1, attempts: 2, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false};
+    let fallback = Fallback::new(err::<u64, _>(exhausted()), ok::<u64, u64>(42));
+    assert_eq!(fallback.wait(), Ok(42));
+
+    let fallback = Fallback::new(err::<u64, _>(exhausted()), err::<u64, u64>(2));
+    assert_eq!(fallback.wait(), Err(FallbackError::Fallback{primary: exhausted(), fallback: 2}));
+
+    let fallback = Fallback::new(err::<u64, _>(RetryError::OperationError::<u64, ()>(1)), ok::<u64, u64>(42));
+    assert_eq!(fallback.wait(), Err(FallbackError::Primary(RetryError::OperationError(1))));
+}
+
+#[test]
+fn recovers_with_a_fallback_value() {
+    use futures::future::{err, ok};
+
+    assert_eq!(Recover::new(err::<u64, u64>(1), |err| err + 1).wait(), Ok(2));
+    assert_eq!(Recover::new(ok::<u64, u64>(42), |err| err + 1).wait(), Ok(42));
+}
@@ -1,18 +1,39 @@
-use either::Either;
-use futures::{Async, IntoFuture, Future, Poll};
-use futures::future::{Flatten, FutureResult};
-use std::iter::{Iterator, IntoIterator};
+use futures::{task, Async, IntoFuture, Future, Poll, try_ready};
+use futures::future::{Executor, Flatten, FutureResult, MapErr};
 use std::error::Error;
 use std::io;
 use std::cmp;
+use std::convert::Infallible;
+use std::collections::VecDeque;
+use std::collections::vec_deque;
 use std::fmt;
-use std::time::Duration;
+use std::hash::Hash;
+#[cfg(feature = "tokio_timer")]
+use std::sync::OnceLock;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 #[cfg(feature = "tokio_timer")]
 use tokio_timer;
 #[cfg(feature = "tokio_core")]
 use tokio_core::reactor;
 
-use super::Action;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, ActionWithContext, WithPreviousError, Cancellable, Deadline, Fallback, Hedge, HedgeError, ReclaimAction, Recover, UntilShutdown, RetryStats, Timeout, TimeoutError, Until, UntilError, WithStats};
+use super::hook::{EventSender, NoHook, OnRetry, RetryHook};
+#[cfg(feature = "tracing")]
+use super::hook::TraceErrors;
+use super::handle::{self, AbortSignal, Pause, RetryHandle};
+use super::limiter::{KeyedLimiter, RetryLimit};
+use super::supervisor::{Exit, Supervised, Supervisor};
+use super::isolate::{Attempt, Isolated, IsolationError};
+use super::unwind::{CatchUnwind, UnwindError};
+use super::telemetry::Telemetry;
+use super::stats::MAX_LATENCIES;
+use super::watch::{RetryOutcome, RetryWatch, StatusSender};
+use super::condition::{Always, Condition, RetryContext, RetryableError, Retryable};
+use super::strategy::{budgeted, BackoffStrategy, Budgeted, IntoStrategy, RetryBudget};
 
 pub trait Sleep {
     type Future: Future;
@@ -27,6 +48,33 @@ impl Sleep for tokio_timer::Timer {
     }
 }
 
+/// Sleeps on a `tokio_timer::Timer` shared by the whole process, which is created lazily
+/// when it is first used.
+///
+/// This allows libraries to retry operations without exposing timer plumbing to their users.
+///
+/// Unlike `tokio_timer::Timer::default()`, which refuses sleeps beyond 409.6 seconds, the timer
+/// accepts sleeps of up to `DefaultTimer::MAX_SLEEP`, so that long backoff caps and deadlines
+/// do not fail with `TimerError::TooLong`.
+#[cfg(feature = "tokio_timer")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultTimer;
+
+#[cfg(feature = "tokio_timer")]
+impl DefaultTimer {
+    /// The longest sleep the timer accepts, which is a day.
+    pub const MAX_SLEEP: Duration = Duration::from_secs(24 * 60 * 60);
+}
+
+#[cfg(feature = "tokio_timer")]
+impl Sleep for DefaultTimer {
+    type Future = tokio_timer::Sleep;
+    fn sleep(&mut self, duration: Duration) -> Self::Future {
+        static TIMER: OnceLock<tokio_timer::Timer> = OnceLock::new();
+        TIMER.get_or_init(|| tokio_timer::wheel().max_timeout(DefaultTimer::MAX_SLEEP).build()).sleep(duration)
+    }
+}
+
 #[cfg(feature = "tokio_core")]
 impl Sleep for reactor::Handle {
     type Future = Flatten<FutureResult<reactor::Timeout, io::Error>>;
@@ -36,19 +84,63 @@ impl Sleep for reactor::Handle {
 }
 
 /// Represents the errors possible during the execution of the `RetryFuture`.
+///
+/// Errors which were not retried because of the condition are reported as
+/// `OperationError`, while `RetriesExhausted` is reported once the strategy ran out,
+/// and `Rejected` once a fail-fast limiter refused to let the sequence retry. Failures to
+/// prepare a retry via `RetryFuture::before_retry` are reported as `PreparationError`.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum RetryError<OE, TE> {
     OperationError(OE),
-    TimerError(TE)
+    TimerError(TE),
+    /// Preparing the next attempt, see `RetryFuture::before_retry`, failed.
+    PreparationError(OE),
+    /// The strategy ran out of delays.
+    RetriesExhausted {
+        /// The error of the last attempt.
+        last_error: OE,
+        /// The number of attempts made, including the initial one.
+        attempts: usize,
+        /// The time elapsed since the initial attempt was started.
+        total_elapsed: Duration,
+        /// The errors of the attempts before the last one, oldest first,
+        /// as far as kept via `RetryFuture::keep_errors`.
+        recent_errors: Vec<OE>,
+        /// Whether the strategy gave up because the maximum number of attempts was reached,
+        /// see `StrategyExt::max_attempts`, rather than on its own, e.g. out of time.
+        attempts_exhausted: bool
+    },
+    /// A fail-fast [`KeyedLimiter`](struct.KeyedLimiter.html) refused the retry,
+    /// as the limit of its key was reached.
+    Rejected {
+        /// The error of the last attempt.
+        last_error: OE,
+        /// The number of attempts made, including the initial one.
+        attempts: usize,
+        /// The time elapsed since the initial attempt was started.
+        total_elapsed: Duration
+    }
 }
 
+/// Timer errors never compare equal, and the elapsed time of `RetriesExhausted`
+/// and `Rejected` is not compared.
 impl<OE: cmp::PartialEq, TE> cmp::PartialEq for RetryError<OE, TE> {
     fn eq(&self, other: &RetryError<OE, TE>) -> bool  {
         match (self, other) {
             (&RetryError::TimerError(_), _) => false,
             (_, &RetryError::TimerError(_)) => false,
-            (&RetryError::OperationError(ref left_err), &RetryError::OperationError(ref right_err)) =>
-                left_err.eq(right_err)
+            (RetryError::OperationError(left_err), RetryError::OperationError(right_err)) =>
+                left_err.eq(right_err),
+            (RetryError::PreparationError(left_err), RetryError::PreparationError(right_err)) =>
+                left_err.eq(right_err),
+            (RetryError::RetriesExhausted{last_error: left_err, attempts: left_attempts, recent_errors: left_recent, attempts_exhausted: left_exhausted, ..},
+             RetryError::RetriesExhausted{last_error: right_err, attempts: right_attempts, recent_errors: right_recent, attempts_exhausted: right_exhausted, ..}) =>
+                left_err.eq(right_err) && left_attempts == right_attempts && left_recent.eq(right_recent) && left_exhausted == right_exhausted,
+            (RetryError::Rejected{last_error: left_err, attempts: left_attempts, ..},
+             RetryError::Rejected{last_error: right_err, attempts: right_attempts, ..}) =>
+                left_err.eq(right_err) && left_attempts == right_attempts,
+            _ => false
         }
     }
 }
@@ -57,90 +149,804 @@ impl<OE: fmt::Display, TE: fmt::Display> fmt::Display for RetryError<OE, TE> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
             RetryError::OperationError(ref err) => err.fmt(formatter),
-            RetryError::TimerError(ref err) => err.fmt(formatter)
+            RetryError::TimerError(ref err) => err.fmt(formatter),
+            RetryError::PreparationError(ref err) => write!(formatter, "preparing the next attempt failed: {}", err),
+            RetryError::RetriesExhausted{ref last_error, attempts, total_elapsed, ..} =>
+                write!(formatter, "{} (gave up after {} attempts over {:?})", last_error, attempts, total_elapsed),
+            RetryError::Rejected{ref last_error, attempts, ..} =>
+                write!(formatter, "{} (retry rejected by the limiter after {} attempts)", last_error, attempts)
         }
     }
 }
 
 impl<OE: Error, TE: Error> Error for RetryError<OE, TE> {
+    #[allow(deprecated)]
     fn description(&self) -> &str {
         match *self {
-            RetryError::OperationError(ref err) => err.description(),
-            RetryError::TimerError(ref err) => err.description()
+            RetryError::OperationError(ref err) | RetryError::PreparationError(ref err) => err.description(),
+            RetryError::TimerError(ref err) => err.description(),
+            RetryError::RetriesExhausted{ref last_error, ..} | RetryError::Rejected{ref last_error, ..} => last_error.description()
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            RetryError::OperationError(ref err) | RetryError::PreparationError(ref err) => Some(err),
+            RetryError::TimerError(ref err) => Some(err),
+            RetryError::RetriesExhausted{ref last_error, ..} | RetryError::Rejected{ref last_error, ..} => Some(last_error)
+        }
+    }
+}
+
+impl<OE, TE> RetryError<OE, TE> {
+    /// Returns the error of the last attempt, or of preparing the next one, unless sleeping failed.
+    pub fn operation_error(&self) -> Option<&OE> {
+        match *self {
+            RetryError::OperationError(ref err) | RetryError::PreparationError(ref err) => Some(err),
+            RetryError::TimerError(_) => None,
+            RetryError::RetriesExhausted{ref last_error, ..} | RetryError::Rejected{ref last_error, ..} => Some(last_error)
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    /// Returns the timer error, if sleeping failed.
+    pub fn timer_error(&self) -> Option<&TE> {
         match *self {
-            RetryError::OperationError(ref err) => Some(err),
-            RetryError::TimerError(ref err) => Some(err)
+            RetryError::TimerError(ref err) => Some(err),
+            _ => None
+        }
+    }
+
+    /// Maps the operation errors, including any kept recent errors, with the given function.
+    pub fn map_operation_error<F, U>(self, mut f: F) -> RetryError<U, TE> where F: FnMut(OE) -> U {
+        match self {
+            RetryError::OperationError(err) => RetryError::OperationError(f(err)),
+            RetryError::TimerError(err) => RetryError::TimerError(err),
+            RetryError::PreparationError(err) => RetryError::PreparationError(f(err)),
+            RetryError::RetriesExhausted{last_error, attempts, total_elapsed, recent_errors, attempts_exhausted} => {
+                let recent_errors = recent_errors.into_iter().map(&mut f).collect();
+                RetryError::RetriesExhausted{last_error: f(last_error), attempts, total_elapsed, recent_errors, attempts_exhausted}
+            },
+            RetryError::Rejected{last_error, attempts, total_elapsed} =>
+                RetryError::Rejected{last_error: f(last_error), attempts, total_elapsed}
+        }
+    }
+
+    /// Returns the error of the last attempt, or of preparing the next one,
+    /// or the timer error if sleeping failed.
+    pub fn into_inner(self) -> Result<OE, TE> {
+        match self {
+            RetryError::OperationError(err) | RetryError::PreparationError(err) => Ok(err),
+            RetryError::TimerError(err) => Err(err),
+            RetryError::RetriesExhausted{last_error, ..} | RetryError::Rejected{last_error, ..} => Ok(last_error)
+        }
+    }
+}
+
+impl<OE: Into<io::Error>, TE: Error + Send + Sync + 'static> RetryError<OE, TE> {
+    /// Converts the error into an `io::Error` of the kind of the last operation error,
+    /// or of kind `Other` if sleeping failed.
+    ///
+    /// The `io::Error` wraps the whole `RetryError<io::Error, TE>`, so that e.g. the number
+    /// of attempts and the recent errors stay reachable via `io::Error::get_ref` or
+    /// `io::Error::into_inner`, and downcasting.
+    pub fn into_io_error(self) -> io::Error {
+        let err = self.map_operation_error(Into::into);
+        let kind = err.operation_error().map_or(io::ErrorKind::Other, io::Error::kind);
+        io::Error::new(kind, err)
+    }
+}
+
+impl<TE: Error + Send + Sync + 'static> From<RetryError<io::Error, TE>> for io::Error {
+    fn from(err: RetryError<io::Error, TE>) -> io::Error {
+        err.into_io_error()
+    }
+}
+
+impl<OE> RetryError<OE, Infallible> {
+    /// Returns the operation error, as sleeping between attempts cannot fail.
+    pub fn into_operation_error(self) -> OE {
+        match self {
+            RetryError::OperationError(err) | RetryError::PreparationError(err) => err,
+            RetryError::TimerError(never) => match never {},
+            RetryError::RetriesExhausted{last_error, ..} | RetryError::Rejected{last_error, ..} => last_error
         }
     }
 }
 
-enum RetryState<S, A> where S: Sleep, A: Action {
+type IntoOperationError<OE> = fn(RetryError<OE, Infallible>) -> OE;
+
+type WithEvents<S, I, A, C, H> = RetryFuture<S, I, A, C, (H, EventSender<<A as Action>::Error>)>;
+
+type Preparation<E> = Box<dyn Future<Item=(), Error=E> + Send>;
+
+type Prepare<E> = Box<dyn FnMut(usize) -> Preparation<E> + Send>;
+
+/// The number of state transitions after which a single poll yields to the executor.
+pub(crate) const DEFAULT_YIELD_AFTER: usize = 64;
+
+type StepPoll<S, A> = Poll<(), RetryError<<A as Action>::Error, <<S as Sleep>::Future as Future>::Error>>;
+
+type RetryPoll<S, A> = Poll<<A as Action>::Item, RetryError<<A as Action>::Error, <<S as Sleep>::Future as Future>::Error>>;
+
+enum RetryState<S, A, C> where S: Sleep, A: Action, C: Condition<A::Error> {
+    Pending,
     Running(A::Future),
-    Sleeping(S::Future)
+    Checking(C::Future, Option<A::Error>, RetryContext),
+    Sleeping(S::Future),
+    Preparing(Preparation<A::Error>),
+    Paused,
+    Limited
 }
 
 /// Future that drives multiple attempts at an action via a retry strategy.
-pub struct RetryFuture<S, I, A> where S: Sleep, I: Iterator<Item=Duration>, A: Action {
+pub struct RetryFuture<S, I, A, C = Always, H = NoHook> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
     strategy: I,
-    state: RetryState<S, A>,
+    condition: C,
+    hook: H,
+    attempt: usize,
+    started: Instant,
+    attempt_started: Instant,
+    latencies: VecDeque<Duration>,
+    total_delay: Duration,
+    delay: Duration,
+    next_attempt: Option<Instant>,
+    recent_errors: VecDeque<A::Error>,
+    previous_error: Option<A::Error>,
+    keep_errors: usize,
+    yield_after: usize,
+    watch: Option<StatusSender>,
+    pause: Option<Arc<Pause>>,
+    limit: Option<RetryLimit>,
+    prepare: Option<Prepare<A::Error>>,
+    telemetry: Telemetry,
+    state: RetryState<S, A, C>,
     action: A,
     sleep: S
 }
 
-impl<S, I, A> RetryFuture<S, I, A> where S: Sleep, I: Iterator<Item=Duration>, A: Action {
-    pub fn spawn<T: IntoIterator<IntoIter=I, Item=Duration>>(sleep: S, strategy: T, mut action: A) -> RetryFuture<S, I, A> {
+impl<S, A, C> fmt::Debug for RetryState<S, A, C> where S: Sleep, A: Action, C: Condition<A::Error> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.write_str(match *self {
+            RetryState::Pending => "Pending",
+            RetryState::Running(_) => "Running",
+            RetryState::Checking(..) => "Checking",
+            RetryState::Sleeping(_) => "Sleeping",
+            RetryState::Preparing(_) => "Preparing",
+            RetryState::Paused => "Paused",
+            RetryState::Limited => "Limited"
+        })
+    }
+}
+
+impl<S, I, A, C, H> fmt::Debug for RetryFuture<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error> + fmt::Debug, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("RetryFuture")
+            .field("name", &self.telemetry.name())
+            .field("state", &self.state)
+            .field("attempt", &self.attempt)
+            .field("strategy", &self.strategy)
+            .field("total_delay", &self.total_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, A> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action {
+    /// Constructs a retry future, retrying the action with the delays of the strategy.
+    ///
+    /// The action is first run when the future is first polled, not on construction,
+    /// so that the builder methods apply to the first attempt as well.
+    ///
+    /// Delays longer than the timer accepts fail the retry sequence with `RetryError::TimerError`.
+    /// This includes delays beyond 409.6 seconds on `tokio_timer::Timer::default()`, so long
+    /// backoff caps call for a timer built with a larger `max_timeout`, or the
+    /// [`DefaultTimer`](struct.DefaultTimer.html).
+    pub fn spawn<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A) -> RetryFuture<S, I, A> {
+        RetryFuture::new(sleep, strategy.into_strategy(), action)
+    }
+
+    /// Like `spawn`, but names the retry sequence, see `named`.
+    pub fn spawn_named<T: IntoStrategy<Strategy=I>>(sleep: S, name: &'static str, strategy: T, action: A) -> RetryFuture<S, I, A> {
+        RetryFuture::spawn(sleep, strategy, action).named(name)
+    }
+
+    pub(crate) fn new(sleep: S, strategy: I, action: A) -> RetryFuture<S, I, A> {
+        RetryFuture::with_condition(sleep, strategy, action, Always)
+    }
+
+    /// Like `spawn`, but resolves to the [`Outcome`](struct.Outcome.html) of the retry,
+    /// which includes statistics about the attempts it took besides the result.
+    pub fn spawn_with_stats<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A) -> WithStats<S, I, A, Always, NoHook> {
+        RetryFuture::spawn(sleep, strategy, action).with_stats()
+    }
+}
+
+#[cfg(feature = "tokio_timer")]
+impl<I, A> RetryFuture<DefaultTimer, I, A> where I: BackoffStrategy<A::Error>, A: Action {
+    /// Like `spawn`, but sleeps on the [`DefaultTimer`](struct.DefaultTimer.html)
+    /// instead of requiring one to be passed in.
+    pub fn spawn_default<T: IntoStrategy<Strategy=I>>(strategy: T, action: A) -> RetryFuture<DefaultTimer, I, A> {
+        RetryFuture::spawn(DefaultTimer, strategy, action)
+    }
+}
+
+impl<S, I, A> RetryFuture<S, Budgeted<I>, A> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action {
+    /// Like `spawn`, but bounds the retries by the given budget shared with other calls,
+    /// see [`RetryBudget`](strategy/struct.RetryBudget.html).
+    pub fn spawn_with_budget<T: IntoStrategy<Strategy=I>>(sleep: S, budget: RetryBudget, strategy: T, action: A) -> RetryFuture<S, Budgeted<I>, A> {
+        RetryFuture::spawn(sleep, budgeted(budget, strategy), action)
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, Supervised<A>> where S: Sleep, I: BackoffStrategy<Exit<A::Item, A::Error>>, A: Action {
+    /// Supervises a long-running future, such as a daemon or an actor, created by the given
+    /// action: whenever it terminates, whether it failed or completed, it is re-created after
+    /// the delay of the strategy, until the strategy gives up.
+    ///
+    /// Hooks, telemetry and handles apply to the restarts like to any other retry.
+    pub fn respawn<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A) -> Supervisor<S, I, A> {
+        RetryFuture::spawn(sleep, strategy, Supervised::new(action))
+    }
+}
+
+impl<S, I, F> RetryFuture<S, I, ActionWithContext<F>> where S: Sleep, ActionWithContext<F>: Action, I: BackoffStrategy<<ActionWithContext<F> as Action>::Error> {
+    /// Like `spawn`, but passes the [`RetryContext`](condition/struct.RetryContext.html)
+    /// of each attempt to the closure.
+    pub fn spawn_with_context<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, f: F) -> RetryFuture<S, I, ActionWithContext<F>> {
+        RetryFuture::spawn(sleep, strategy, ActionWithContext::new(f))
+    }
+}
+
+impl<S, I, F, E> RetryFuture<S, I, WithPreviousError<F, E>> where S: Sleep, WithPreviousError<F, E>: Action<Error=E>, I: BackoffStrategy<E> {
+    /// Like `spawn`, but passes the error of the previous attempt, if any, to the closure.
+    ///
+    /// This allows e.g. reconnect logic to decide what to rebuild depending on why
+    /// the last attempt failed.
+    pub fn spawn_with_previous_error<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, f: F) -> RetryFuture<S, I, WithPreviousError<F, E>> {
+        RetryFuture::spawn(sleep, strategy, WithPreviousError::new(f))
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, A, Retryable> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, A::Error: RetryableError {
+    /// Like `spawn`, but only retries errors which report to be
+    /// [retryable](trait.RetryableError.html).
+    pub fn spawn_transient<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A) -> RetryFuture<S, I, A, Retryable> {
+        RetryFuture::with_condition(sleep, strategy.into_strategy(), action, Retryable)
+    }
+}
+
+impl<S, I, A, P> RetryFuture<S, I, Until<A, P>> where S: Sleep, I: BackoffStrategy<UntilError<A::Item, A::Error>>, A: Action, P: Fn(&A::Item) -> bool {
+    /// Like `spawn`, but also retries while the result of the action does not satisfy the predicate.
+    ///
+    /// This allows e.g. polling a job until it reports to be complete. Should the
+    /// strategy run out, the last unsatisfactory result is reported as `UntilError::Unsatisfied`.
+    pub fn spawn_until<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A, predicate: P) -> RetryFuture<S, I, Until<A, P>> {
+        RetryFuture::new(sleep, strategy.into_strategy(), Until::new(action, predicate))
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, Timeout<A, S>> where S: Sleep + Clone, I: BackoffStrategy<TimeoutError<A::Error, <S::Future as Future>::Error>>, A: Action {
+    /// Like `spawn`, but bounds each attempt by the given timeout.
+    ///
+    /// Attempts which take longer fail with `TimeoutError::TimedOut`, and are retried
+    /// like any other failure, so that a single hung attempt does not block the retry
+    /// sequence forever.
+    pub fn spawn_with_timeout<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, timeout: Duration, action: A) -> RetryFuture<S, I, Timeout<A, S>> {
+        let action = Timeout::new(action, sleep.clone(), timeout);
+        RetryFuture::new(sleep, strategy.into_strategy(), action)
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, Hedge<A, S>> where S: Sleep + Clone, I: BackoffStrategy<HedgeError<A::Error, <S::Future as Future>::Error>>, A: Action + Clone {
+    /// Like `spawn`, but hedges attempts which have not completed within the given delay
+    /// with a second, speculative attempt, see [`Hedge`](struct.Hedge.html).
+    ///
+    /// This mitigates tail latencies with a bounded amount of extra load, as at most one
+    /// speculative attempt is started per attempt.
+    pub fn spawn_hedged<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, delay: Duration, action: A) -> RetryFuture<S, I, Hedge<A, S>> {
+        let action = Hedge::new(action, sleep.clone(), delay);
+        RetryFuture::new(sleep, strategy.into_strategy(), action)
+    }
+}
+
+impl<S, I, A, X> RetryFuture<S, I, Isolated<A, X>> where S: Sleep, I: BackoffStrategy<IsolationError<A::Error>>, A: Action, X: Executor<Attempt<A::Future>> {
+    /// Like `spawn`, but spawns every attempt onto the executor as a task of its own,
+    /// see [`Isolated`](struct.Isolated.html).
+    ///
+    /// Attempts which are abandoned, e.g. because the retry future is dropped, are cancelled
+    /// the next time their task is polled, whether or not the action supports cancellation.
+    pub fn spawn_isolated<T: IntoStrategy<Strategy=I>>(sleep: S, executor: X, strategy: T, action: A) -> RetryFuture<S, I, Isolated<A, X>> {
+        RetryFuture::spawn(sleep, strategy, Isolated::new(executor, action))
+    }
+}
+
+impl<S, I, A> RetryFuture<S, I, CatchUnwind<A>> where S: Sleep, I: BackoffStrategy<UnwindError<A::Error>>, A: Action {
+    /// Like `spawn`, but catches panics of attempts, which then fail with `UnwindError::Panicked`,
+    /// and flow through conditions and strategies like any other failure.
+    ///
+    /// Once retries are exhausted, the panic can be re-raised via `UnwindError::resume_unwind`.
+    pub fn spawn_catch_unwind<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A) -> RetryFuture<S, I, CatchUnwind<A>> {
+        RetryFuture::spawn(sleep, strategy, CatchUnwind::new(action))
+    }
+}
+
+impl<S, I, A, C> RetryFuture<S, I, A, C> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error> {
+    /// Like `spawn`, but only retries errors for which the condition holds.
+    ///
+    /// The condition may be asynchronous, see the [`condition`](condition/index.html) module.
+    pub fn spawn_if<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, action: A, condition: C) -> RetryFuture<S, I, A, C> {
+        RetryFuture::with_condition(sleep, strategy.into_strategy(), action, condition)
+    }
+
+    pub(crate) fn with_condition(sleep: S, strategy: I, action: A, condition: C) -> RetryFuture<S, I, A, C> {
+        RetryFuture {
+            strategy,
+            condition,
+            hook: NoHook,
+            attempt: 0,
+            started: Instant::now(),
+            attempt_started: Instant::now(),
+            latencies: VecDeque::new(),
+            total_delay: Duration::ZERO,
+            delay: Duration::ZERO,
+            next_attempt: None,
+            recent_errors: VecDeque::new(),
+            previous_error: None,
+            keep_errors: 0,
+            yield_after: DEFAULT_YIELD_AFTER,
+            watch: None,
+            pause: None,
+            limit: None,
+            prepare: None,
+            telemetry: Telemetry::new(),
+            state: RetryState::Pending,
+            action,
+            sleep
+        }
+    }
+
+}
+
+impl<S, I, A, C, H> RetryFuture<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    /// Calls the given function before each retry, with the error of the failed attempt,
+    /// the number of that attempt and the delay before the next one.
+    ///
+    /// This is useful e.g. to log every retry. Functions registered by repeated calls
+    /// are called in order.
+    pub fn on_retry<F: FnMut(&A::Error, usize, Duration)>(self, f: F) -> RetryFuture<S, I, A, C, (H, OnRetry<F>)> {
+        self.with_hook(OnRetry::new(f))
+    }
+
+    /// Adds the given [`RetryHook`](trait.RetryHook.html), to be called after the ones configured so far.
+    pub fn with_hook<G: RetryHook<A::Error>>(self, hook: G) -> RetryFuture<S, I, A, C, (H, G)> {
         RetryFuture {
-            strategy: strategy.into_iter(),
-            state: RetryState::Running(action.run()),
-            action: action,
-            sleep: sleep
+            strategy: self.strategy,
+            condition: self.condition,
+            hook: (self.hook, hook),
+            attempt: self.attempt,
+            started: self.started,
+            attempt_started: self.attempt_started,
+            latencies: self.latencies,
+            total_delay: self.total_delay,
+            delay: self.delay,
+            next_attempt: self.next_attempt,
+            recent_errors: self.recent_errors,
+            previous_error: self.previous_error,
+            keep_errors: self.keep_errors,
+            yield_after: self.yield_after,
+            watch: self.watch,
+            pause: self.pause,
+            limit: self.limit,
+            prepare: self.prepare,
+            telemetry: self.telemetry,
+            state: self.state,
+            action: self.action,
+            sleep: self.sleep
+        }
+    }
+
+    /// Emits a `tracing` event with the error of each failed attempt before it is retried.
+    ///
+    /// This requires the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    pub fn trace_errors(self) -> RetryFuture<S, I, A, C, (H, TraceErrors)> where A::Error: fmt::Display {
+        self.with_hook(TraceErrors)
+    }
+
+    /// Sets the target of the log records emitted for this retry sequence,
+    /// which defaults to `tokio_retry`.
+    ///
+    /// This requires the `log` feature.
+    #[cfg(feature = "log")]
+    pub fn log_target(mut self, target: &'static str) -> RetryFuture<S, I, A, C, H> {
+        self.telemetry.set_log_target(target);
+        self
+    }
+
+    /// Names this retry sequence after the logical operation it performs.
+    ///
+    /// The name is recorded in the `tracing` span, prefixes `log` records and labels
+    /// metrics as `policy`, so that telemetry can be aggregated per operation.
+    pub fn named(mut self, name: &'static str) -> RetryFuture<S, I, A, C, H> {
+        self.telemetry.set_name(name);
+        self
+    }
+
+    /// Returns the name given via `named`, if any.
+    pub fn name(&self) -> Option<&'static str> {
+        self.telemetry.name()
+    }
+
+    /// Reports the lifecycle of this retry sequence as [`RetryEvent`](enum.RetryEvent.html)s
+    /// to the given channel, e.g. to observe it from a dashboard or a test.
+    pub fn send_events(self, sender: EventSender<A::Error>) -> WithEvents<S, I, A, C, H> where A::Error: Clone {
+        self.with_hook(sender)
+    }
+
+    /// Runs the given asynchronous preparation before each retry, once the delay has elapsed,
+    /// with the number of the upcoming attempt.
+    ///
+    /// This allows e.g. refreshing an auth token or re-resolving DNS between attempts.
+    /// Should the preparation fail, the retry sequence is aborted with its error as
+    /// `RetryError::PreparationError`.
+    pub fn before_retry<F, P>(mut self, mut f: F) -> RetryFuture<S, I, A, C, H>
+        where F: FnMut(usize) -> P + Send + 'static, P: IntoFuture<Item=(), Error=A::Error>, P::Future: Send + 'static {
+        self.prepare = Some(Box::new(move |attempt| Box::new(f(attempt).into_future())));
+        self
+    }
+
+    /// Bounds the whole retry sequence by the given wall-clock deadline, starting now.
+    ///
+    /// Once it has passed, the retry sequence is aborted with `DeadlineError::DeadlineExceeded`,
+    /// even while an attempt or a delay is still in progress. Should sleeping until the deadline
+    /// fail, the retry sequence is aborted with `DeadlineError::TimerError`.
+    pub fn with_deadline(mut self, deadline: Duration) -> Deadline<Self, S::Future> {
+        let timer = self.sleep.sleep(deadline);
+        Deadline::new(self, timer, deadline)
+    }
+
+    /// Runs the given fallback once the retry sequence has exhausted its retries, e.g. another
+    /// retry sequence, with its own strategy, reading from a replica.
+    ///
+    /// Should the fallback fail as well, both errors are reported via `FallbackError::Fallback`.
+    /// Other failures are reported via `FallbackError::Primary`, without running the fallback.
+    pub fn or_else_try<F: IntoFuture<Item=A::Item>>(self, fallback: F) -> Fallback<Self, F> {
+        Fallback::new(self, fallback)
+    }
+
+    /// Resolves to the value computed by the given closure from the final error,
+    /// should the retry sequence fail.
+    ///
+    /// Hooks and telemetry still report the failure, e.g. as exhausted retries, before the
+    /// fallback value is served.
+    pub fn unwrap_or_else_after_retries<F>(self, f: F) -> Recover<Self, F>
+        where F: FnOnce(RetryError<A::Error, <S::Future as Future>::Error>) -> A::Item {
+        Recover::new(self, f)
+    }
+
+    /// Aborts the retry sequence with `CancelError::Cancelled` once the given signal completes,
+    /// even while an attempt or a delay is still in progress.
+    ///
+    /// Any future can serve as signal, e.g. the receiving end of a `oneshot` channel
+    /// which is triggered on shutdown.
+    pub fn cancel_on<F: Future>(self, signal: F) -> Cancellable<Self, F> {
+        Cancellable::new(self, signal)
+    }
+
+    /// Races the retry sequence against the given shutdown signal, resolving to `None`
+    /// should the signal complete first.
+    ///
+    /// Unlike `cancel_on`, this spares services mapping the shutdown to an error for clean termination.
+    pub fn until<F: Future>(self, shutdown: F) -> UntilShutdown<Self, F> {
+        UntilShutdown::new(self, shutdown)
+    }
+
+    /// Returns this future along with a [`RetryHandle`](struct.RetryHandle.html), which can abort,
+    /// pause and resume the retry sequence and query its current attempt from another task.
+    pub fn with_handle(mut self) -> (Cancellable<Self, AbortSignal>, RetryHandle) {
+        let (signal, pause, handle) = handle::pair(self.watch());
+        self.pause = Some(pause);
+        (self.cancel_on(signal), handle)
+    }
+
+    /// Limits the retries of this sequence by the given limiter, counting against the limit
+    /// of the given key, e.g. the downstream host, see [`KeyedLimiter`](struct.KeyedLimiter.html).
+    pub fn limit_retries<K: Hash + Eq + Clone + Send + 'static>(mut self, limiter: &KeyedLimiter<K>, key: K) -> RetryFuture<S, I, A, C, H> {
+        self.limit = Some(limiter.limit(key));
+        self
+    }
+
+    /// Yields to the executor after the given number of consecutive state transitions
+    /// within a single poll, which defaults to 64.
+    ///
+    /// This keeps retry loops with zero or sub-timer-resolution delays from starving other
+    /// tasks, as the executor gets to run them before the retry loop continues.
+    pub fn yield_after(mut self, transitions: usize) -> RetryFuture<S, I, A, C, H> {
+        self.yield_after = cmp::max(transitions, 1);
+        self
+    }
+
+    /// Keeps the errors of up to `count` of the most recent failed attempts.
+    ///
+    /// Kept errors are reported by `RetryError::RetriesExhausted`, besides the last one,
+    /// and by the [`Outcome`](struct.Outcome.html) of `with_stats` on success, which keeps
+    /// memory bounded even for strategies which retry forever.
+    pub fn keep_errors(mut self, count: usize) -> RetryFuture<S, I, A, C, H> {
+        self.keep_errors = count;
+        self
+    }
+
+    /// Resolves to the [`Outcome`](struct.Outcome.html) of the retry, which includes
+    /// statistics about the attempts it took besides the result.
+    pub fn with_stats(self) -> WithStats<S, I, A, C, H> {
+        WithStats::new(self)
+    }
+
+    /// Fails with the operation error alone, for sleepers which cannot fail.
+    ///
+    /// This lets `?` convert the error like the one of the action itself.
+    pub fn without_timer_errors(self) -> MapErr<Self, IntoOperationError<A::Error>> where S::Future: Future<Error=Infallible> {
+        self.map_err(RetryError::into_operation_error)
+    }
+
+    /// Returns statistics about the attempts made so far.
+    pub fn stats(&self) -> RetryStats {
+        RetryStats::new(self.attempt, self.latencies.iter().cloned().collect(), self.total_delay, self.started.elapsed())
+    }
+
+    /// Consumes this future, returning the action along with any state it accumulated.
+    pub fn into_inner(self) -> A {
+        self.action
+    }
+
+    /// Resolves to the outcome along with the action, so that e.g. a partially-warmed client
+    /// it owns can be reused once retries are exhausted, instead of being dropped.
+    pub fn reclaim_action(self) -> ReclaimAction<S, I, A, C, H> {
+        ReclaimAction::new(self)
+    }
+
+    /// Returns the number of attempts started so far, including the current one.
+    pub fn attempts(&self) -> usize {
+        self.attempt
+    }
+
+    /// Returns whether this future is waiting for the delay before the next attempt.
+    pub fn is_sleeping(&self) -> bool {
+        matches!(self.state, RetryState::Sleeping(_))
+    }
+
+    /// Returns the time remaining until the next attempt, while sleeping.
+    pub fn next_delay_hint(&self) -> Option<Duration> {
+        if !self.is_sleeping() {
+            return None;
         }
+        self.next_attempt.map(|next_attempt| next_attempt.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns a receiver of the live state of this future, i.e. the current attempt,
+    /// when the next one is scheduled, and eventually how the future finished.
+    pub fn watch(&mut self) -> RetryWatch {
+        let attempt = self.attempt;
+        self.watch.get_or_insert_with(|| StatusSender::new(attempt)).subscribe()
+    }
+
+    /// Returns the kept errors of the most recent failed attempts, oldest first.
+    pub fn recent_errors(&self) -> vec_deque::Iter<'_, A::Error> {
+        self.recent_errors.iter()
+    }
+
+    pub(crate) fn take_recent_errors(&mut self) -> Vec<A::Error> {
+        self.recent_errors.drain(..).collect()
     }
 
-    fn attempt(&mut self) -> Poll<A::Item, RetryError<A::Error, <S::Future as Future>::Error>> {
-        let future = self.action.run();
+    /// Starts the next attempt, unless held back while paused.
+    fn attempt(&mut self) -> StepPoll<S, A> {
+        if let Some(ref pause) = self.pause {
+            if !pause.poll_resumed() {
+                self.state = RetryState::Paused;
+                return Ok(Async::NotReady);
+            }
+        }
+        if self.attempt > 0 {
+            if let Some(ref mut limit) = self.limit {
+                if !limit.poll_acquire() {
+                    self.state = RetryState::Limited;
+                    return Ok(Async::NotReady);
+                }
+            }
+        }
+
+        self.attempt += 1;
+        self.telemetry.attempt_started(self.attempt, self.delay);
+        self.hook.on_attempt(self.attempt);
+        self.attempt_started = Instant::now();
+        if self.attempt == 1 {
+            self.started = self.attempt_started;
+        }
+        if let Some(ref watch) = self.watch {
+            watch.update(self.attempt, None);
+        }
+        let future = {
+            let _attempt = self.telemetry.enter_attempt();
+            let previous = match self.keep_errors {
+                0 => self.previous_error.as_ref(),
+                _ => self.recent_errors.back()
+            };
+            let context = RetryContext::new(self.attempt, self.started.elapsed());
+            self.action.run_with_context(previous, &context)
+        };
         self.state = RetryState::Running(future);
-        return self.poll();
+        Ok(Async::Ready(()))
+    }
+
+    fn check(&mut self, err: A::Error) {
+        let context = RetryContext::new(self.attempt, self.started.elapsed());
+        let future = self.condition.should_retry(&err, &context);
+        self.state = RetryState::Checking(future, Some(err), context);
+    }
+
+    /// Keeps the error of a failed attempt, to be passed to the next one.
+    fn keep(&mut self, err: A::Error) {
+        if self.keep_errors == 0 {
+            self.previous_error = Some(err);
+            return;
+        }
+
+        if self.recent_errors.len() == self.keep_errors {
+            self.recent_errors.pop_front();
+        }
+        self.recent_errors.push_back(err);
     }
 
-    fn retry(&mut self, err: A::Error) -> Poll<A::Item, RetryError<A::Error, <S::Future as Future>::Error>> {
-        match self.strategy.next() {
-            None => Err(RetryError::OperationError(err)),
-            Some(duration) => {
-                let future = self.sleep.sleep(duration);
-                self.state = RetryState::Sleeping(future);
-                return self.poll();
+    /// Records the latency of a completed attempt, forgetting the oldest ones beyond `MAX_LATENCIES`.
+    fn record_latency(&mut self, latency: Duration) {
+        if self.latencies.len() == MAX_LATENCIES {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+
+    /// Runs the preparation for the next attempt, if any, and then the attempt itself.
+    fn prepare(&mut self) -> StepPoll<S, A> {
+        let future = match self.prepare {
+            Some(ref mut prepare) => prepare(self.attempt + 1),
+            None => return self.attempt()
+        };
+        self.state = RetryState::Preparing(future);
+        Ok(Async::Ready(()))
+    }
+
+    /// Drives the retry sequence, see `poll`.
+    fn drive(&mut self) -> RetryPoll<S, A> {
+        let _span = self.telemetry.enter();
+        // Every transition into a new state is followed by polling that state,
+        // until one of them is not ready yet or the retry sequence completes.
+        for _ in 0..self.yield_after {
+            match self.state {
+                RetryState::Pending | RetryState::Paused | RetryState::Limited => try_ready!(self.attempt()),
+                RetryState::Running(ref mut future) => {
+                    let polled = {
+                        let _attempt = self.telemetry.enter_attempt();
+                        future.poll()
+                    };
+                    match polled {
+                        Ok(Async::NotReady) => return Ok(Async::NotReady),
+                        Ok(Async::Ready(item)) => {
+                            let elapsed = self.attempt_started.elapsed();
+                            self.record_latency(elapsed);
+                            self.telemetry.succeeded(self.attempt, elapsed);
+                            self.hook.on_success(self.attempt);
+                            self.strategy.succeeded();
+                            return Ok(Async::Ready(item));
+                        },
+                        Err(err) => {
+                            let elapsed = self.attempt_started.elapsed();
+                            self.record_latency(elapsed);
+                            self.telemetry.attempt_failed(self.attempt, elapsed);
+                            self.hook.on_failure(&err, self.attempt);
+                            self.check(err)
+                        }
+                    }
+                },
+                RetryState::Checking(ref mut future, ref mut err, ref context) => {
+                    let retry = {
+                        let failed = err.as_ref().expect("polled RetryFuture after completion");
+                        match self.condition.poll_decision(future, failed, context) {
+                            Ok(Async::NotReady) => return Ok(Async::NotReady),
+                            Ok(Async::Ready(retry)) => retry,
+                            Err(_) => false
+                        }
+                    };
+                    let err = err.take().expect("polled RetryFuture after completion");
+
+                    if !retry {
+                        self.telemetry.gave_up(self.attempt);
+                        return Err(RetryError::OperationError(err));
+                    }
+                    try_ready!(self.retry(err))
+                },
+                RetryState::Sleeping(ref mut future) => {
+                    try_ready!(future.poll().map_err(RetryError::TimerError));
+                    self.total_delay += self.delay;
+                    try_ready!(self.prepare())
+                },
+                RetryState::Preparing(ref mut future) => {
+                    try_ready!(future.poll().map_err(RetryError::PreparationError));
+                    try_ready!(self.attempt())
+                }
             }
         }
+
+        task::current().notify();
+        Ok(Async::NotReady)
+    }
+
+    fn retry(&mut self, err: A::Error) -> StepPoll<S, A> {
+        let duration = match self.strategy.delay(self.attempt, &err) {
+            None => {
+                self.telemetry.exhausted(self.attempt);
+                self.hook.on_exhausted(&err, self.attempt);
+                return Err(RetryError::RetriesExhausted{
+                    last_error: err,
+                    attempts: self.attempt,
+                    total_elapsed: self.started.elapsed(),
+                    recent_errors: self.recent_errors.drain(..).collect(),
+                    attempts_exhausted: self.strategy.attempts_exhausted()
+                })
+            },
+            Some(duration) => duration
+        };
+        let admitted = match self.limit {
+            Some(ref mut limit) => limit.admit(),
+            None => true
+        };
+        if !admitted {
+            self.telemetry.rejected(self.attempt);
+            return Err(RetryError::Rejected{
+                last_error: err,
+                attempts: self.attempt,
+                total_elapsed: self.started.elapsed()
+            });
+        }
+
+        self.telemetry.retrying(self.attempt, duration);
+        self.hook.on_retry(&err, self.attempt, duration);
+        self.keep(err);
+
+        self.delay = duration;
+        if duration == Duration::ZERO {
+            return self.prepare();
+        }
+
+        let future = self.sleep.sleep(duration);
+        self.next_attempt = Some(Instant::now() + duration);
+        if let Some(ref watch) = self.watch {
+            watch.update(self.attempt, self.next_attempt);
+        }
+        self.state = RetryState::Sleeping(future);
+        Ok(Async::Ready(()))
     }
 }
 
-impl<S, I, A> Future for RetryFuture<S, I, A> where S: Sleep, I: Iterator<Item=Duration>, A: Action {
+impl<S, I, A, C, H> Future for RetryFuture<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
     type Item = A::Item;
     type Error = RetryError<A::Error, <S::Future as Future>::Error>;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        let result = match self.state {
-            RetryState::Running(ref mut future) =>
-                Either::Left(future.poll()),
-            RetryState::Sleeping(ref mut future) =>
-                Either::Right(future.poll().map_err(RetryError::TimerError))
-        };
-
-        match result {
-            Either::Left(poll_result) => match poll_result {
-                Ok(async) => Ok(async),
-                Err(err) => self.retry(err)
-            },
-            Either::Right(poll_result) => match poll_result? {
-                Async::NotReady => Ok(Async::NotReady),
-                Async::Ready(_) => self.attempt()
+        let polled = self.drive();
+        // Completed retry sequences no longer count against the limit of their key.
+        if let Some(ref mut limit) = self.limit {
+            if !matches!(polled, Ok(Async::NotReady)) {
+                limit.release();
+            }
+        }
+        if let Some(ref watch) = self.watch {
+            match polled {
+                Ok(Async::NotReady) => {},
+                Ok(Async::Ready(_)) => watch.finish(RetryOutcome::Succeeded),
+                Err(_) => watch.finish(RetryOutcome::Failed)
             }
         }
+        polled
     }
 }
 
@@ -154,7 +960,7 @@ fn attempts_just_once() {
         Err::<(), u64>(42)
     }).wait();
 
-    assert_eq!(res, Err(RetryError::OperationError(42)));
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 42, attempts: 1, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
     assert_eq!(num_calls, 1);
 }
 
@@ -170,7 +976,7 @@ fn attempts_until_max_retries_exceeded() {
         Err::<(), u64>(42)
     }).wait();
 
-    assert_eq!(res, Err(RetryError::OperationError(42)));
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 42, attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
     assert_eq!(num_calls, 3);
 }
 
@@ -193,3 +999,892 @@ fn attempts_until_success() {
     assert_eq!(res, Ok(()));
     assert_eq!(num_calls, 4);
 }
+
+#[test]
+fn attempts_exactly_max_attempts() {
+    use super::strategy::{NoDelay, StrategyExt};
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.max_attempts(3), || {
+        num_calls += 1;
+        Err::<(), u64>(42)
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 42, attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: true}));
+    assert_eq!(num_calls, 3);
+}
+
+#[test]
+fn tells_exhausted_attempts_apart_from_the_strategy_giving_up() {
+    use super::strategy::{NoDelay, StrategyExt};
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(1).max_attempts(3), || {
+        Err::<(), u64>(42)
+    }).wait();
+
+    match res {
+        Err(RetryError::RetriesExhausted{attempts: 2, attempts_exhausted, ..}) => assert!(!attempts_exhausted),
+        _ => panic!("retries were not exhausted")
+    }
+}
+
+#[test]
+fn does_not_sleep_for_zero_delays() {
+    use futures::future::{err, FutureResult};
+    use super::strategy::NoDelay;
+
+    struct NeverSleep;
+
+    impl Sleep for NeverSleep {
+        type Future = FutureResult<(), ()>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            err(())
+        }
+    }
+
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(NeverSleep, NoDelay.take(2), || {
+        num_calls += 1;
+        Err::<(), u64>(42)
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 42, attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
+    assert_eq!(num_calls, 3);
+}
+
+#[test]
+fn reports_success_to_the_strategy() {
+    use super::strategy::AimdBackoff;
+    let strategy = AimdBackoff::new(Duration::from_millis(1), Duration::from_millis(100));
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), strategy.clone(), || {
+        num_calls += 1;
+        if num_calls < 4 {
+            Err::<(), u64>(42)
+        } else {
+            Ok::<(), u64>(())
+        }
+    }).wait();
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(strategy.current_delay(), Duration::from_millis(7));
+}
+
+#[test]
+fn follows_backoff_hints_of_errors() {
+    use super::strategy::{hinted, BackoffHint, FixedInterval};
+
+    #[derive(Debug, PartialEq)]
+    struct Throttled;
+
+    impl BackoffHint for Throttled {
+        fn retry_after(&self) -> Option<Duration> {
+            Some(Duration::from_millis(1))
+        }
+    }
+
+    let s = hinted(FixedInterval::new(Duration::from_secs(3600)).take(2));
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), s, || {
+        num_calls += 1;
+        Err::<(), Throttled>(Throttled)
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: Throttled, attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
+    assert_eq!(num_calls, 3);
+}
+
+#[test]
+fn passes_attempt_numbers_to_the_strategy() {
+    use super::strategy::BackoffStrategy;
+
+    struct Recording(Vec<usize>);
+
+    impl<E> BackoffStrategy<E> for &mut Recording {
+        fn delay(&mut self, attempt: usize, _: &E) -> Option<Duration> {
+            self.0.push(attempt);
+            if attempt < 3 { Some(Duration::ZERO) } else { None }
+        }
+    }
+
+    let mut recording = Recording(Vec::new());
+    let res = RetryFuture::new(tokio_timer::Timer::default(), &mut recording, || Err::<(), u64>(42)).wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 42, attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
+    assert_eq!(recording.0, vec![1, 2, 3]);
+}
+
+#[test]
+fn retries_only_while_the_condition_holds() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn_if(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Err::<(), u64>(num_calls)
+    }, |err: &u64| *err < 3).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(3)));
+    assert_eq!(num_calls, 3);
+}
+
+#[test]
+fn awaits_asynchronous_conditions() {
+    use super::condition::async_condition;
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let condition = async_condition(|err: &u64| {
+        let retry = *err < 2;
+        tokio_timer::Timer::default().sleep(Duration::from_millis(1)).map(move |_| retry)
+    });
+    let res = RetryFuture::spawn_if(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Err::<(), u64>(num_calls)
+    }, condition).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(2)));
+    assert_eq!(num_calls, 2);
+}
+
+#[test]
+fn retries_only_transient_errors() {
+    use super::strategy::NoDelay;
+
+    #[derive(Debug, PartialEq)]
+    enum Failure { Unavailable, Forbidden }
+
+    impl RetryableError for Failure {
+        fn is_retryable(&self) -> bool {
+            *self == Failure::Unavailable
+        }
+    }
+
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn_transient(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Err::<(), Failure>(if num_calls < 3 { Failure::Unavailable } else { Failure::Forbidden })
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(Failure::Forbidden)));
+    assert_eq!(num_calls, 3);
+}
+
+#[test]
+fn decides_on_combined_conditions() {
+    use super::condition::ConditionExt;
+    use super::strategy::NoDelay;
+    let mut consulted = 0;
+    let condition = (|err: &u64| *err < 3).and(|_: &u64| {
+        consulted += 1;
+        true
+    });
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn_if(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Err::<(), u64>(num_calls)
+    }, condition).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(3)));
+    assert_eq!(consulted, 2);
+}
+
+#[test]
+fn passes_the_attempt_number_to_the_condition() {
+    use super::condition::with_context;
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let condition = with_context(|_: &u64, context: &RetryContext| context.attempt() < 2);
+    let res = RetryFuture::spawn_if(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Err::<(), u64>(42)
+    }, condition).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(42)));
+    assert_eq!(num_calls, 2);
+}
+
+#[test]
+fn retries_until_the_result_satisfies_the_predicate() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn_until(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        Ok::<u64, ()>(num_calls)
+    }, |status: &u64| *status == 3).wait();
+
+    assert_eq!(res, Ok(3));
+}
+
+#[test]
+fn returns_the_last_unsatisfactory_result() {
+    use super::strategy::NoDelay;
+    let res = RetryFuture::spawn_until(tokio_timer::Timer::default(), NoDelay.take(2), || Ok::<&str, ()>("pending"), |status: &&str| *status == "complete").wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: UntilError::Unsatisfied("pending"), attempts: 3, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
+}
+
+#[test]
+fn keeps_the_most_recent_errors() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(4), || {
+        num_calls += 1;
+        Err::<(), u64>(num_calls)
+    }).keep_errors(2).wait();
+
+    assert_eq!(res, Err(RetryError::RetriesExhausted{last_error: 5, attempts: 5, total_elapsed: Duration::ZERO, recent_errors: vec![3, 4], attempts_exhausted: false}));
+}
+
+#[test]
+fn reports_statistics_on_success() {
+    use super::strategy::FixedInterval;
+    let mut num_calls = 0;
+    let outcome = RetryFuture::spawn_with_stats(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(5), || {
+        num_calls += 1;
+        if num_calls < 3 { Err(()) } else { Ok(42) }
+    }).wait().unwrap();
+
+    assert_eq!(outcome.value, 42);
+    assert_eq!(outcome.stats.attempts(), 3);
+    assert_eq!(outcome.stats.latencies().len(), 3);
+    assert_eq!(outcome.stats.total_delay(), Duration::from_millis(2));
+}
+
+#[test]
+fn reports_the_most_recent_errors_on_success() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let outcome = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(5), || {
+        num_calls += 1;
+        if num_calls < 4 { Err(num_calls) } else { Ok(42) }
+    }).keep_errors(2).with_stats().wait().unwrap();
+
+    assert_eq!(outcome.value, 42);
+    assert_eq!(outcome.recent_errors, vec![2, 3]);
+}
+
+#[test]
+fn keeps_the_latencies_of_the_most_recent_attempts() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let outcome = RetryFuture::spawn_with_stats(tokio_timer::Timer::default(), NoDelay, || {
+        num_calls += 1;
+        if num_calls < 100 { Err(()) } else { Ok(42) }
+    }).wait().unwrap();
+
+    assert_eq!(outcome.stats.attempts(), 100);
+    assert_eq!(outcome.stats.latencies().len(), MAX_LATENCIES);
+}
+
+#[test]
+fn fails_with_operation_errors_for_infallible_sleepers() {
+    use futures::future::{ok, FutureResult};
+    use super::strategy::NoDelay;
+
+    struct InstantSleep;
+
+    impl Sleep for InstantSleep {
+        type Future = FutureResult<(), Infallible>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            ok(())
+        }
+    }
+
+    let res = RetryFuture::spawn(InstantSleep, NoDelay.take(2), || Err::<(), u64>(42)).without_timer_errors().wait();
+
+    assert_eq!(res, Err(42));
+}
+
+#[test]
+fn unwraps_errors_with_helpers() {
+    let exhausted: RetryError<u64, ()> = RetryError::RetriesExhausted{last_error: 42, attempts: 2, total_elapsed: Duration::ZERO, recent_errors: vec![41], attempts_exhausted: false};
+    let timer: RetryError<u64, ()> = RetryError::TimerError(());
+
+    assert_eq!(exhausted.operation_error(), Some(&42));
+    assert_eq!(timer.operation_error(), None);
+    assert_eq!(timer.timer_error(), Some(&()));
+    assert_eq!(timer.into_inner(), Err(()));
+    assert_eq!(
+        exhausted.map_operation_error(|err| err + 1),
+        RetryError::RetriesExhausted{last_error: 43, attempts: 2, total_elapsed: Duration::ZERO, recent_errors: vec![42], attempts_exhausted: false}
+    );
+}
+
+#[test]
+fn converts_into_io_errors() {
+    let exhausted: RetryError<io::Error, io::Error> = RetryError::RetriesExhausted{
+        last_error: io::Error::new(io::ErrorKind::TimedOut, "timed out"),
+        attempts: 3,
+        total_elapsed: Duration::ZERO,
+        recent_errors: vec![],
+        attempts_exhausted: false
+    };
+    let timer: RetryError<io::Error, io::Error> = RetryError::TimerError(io::Error::other("timer"));
+
+    let converted = io::Error::from(exhausted);
+    assert_eq!(converted.kind(), io::ErrorKind::TimedOut);
+    match converted.get_ref().and_then(|err| err.downcast_ref::<RetryError<io::Error, io::Error>>()) {
+        Some(&RetryError::RetriesExhausted{ref last_error, attempts: 3, ..}) => assert_eq!(last_error.to_string(), "timed out"),
+        _ => panic!("the retry error was lost")
+    }
+    assert_eq!(io::Error::from(timer).kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn calls_the_hook_before_each_retry() {
+    use super::strategy::FixedInterval;
+    let mut retries = Vec::new();
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(2), || Err::<(), u64>(42))
+        .on_retry(|err: &u64, attempt, delay| retries.push((*err, attempt, delay)))
+        .wait();
+
+    assert!(res.is_err());
+    assert_eq!(retries, vec![(42, 1, Duration::from_millis(1)), (42, 2, Duration::from_millis(1))]);
+}
+
+#[test]
+fn sends_lifecycle_events() {
+    use futures::Stream;
+    use futures::sync::mpsc;
+    use super::RetryEvent;
+    use super::strategy::FixedInterval;
+    let (sender, receiver) = mpsc::unbounded();
+    let mut calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(1), || {
+        calls += 1;
+        if calls < 2 { Err(calls) } else { Ok(calls) }
+    }).send_events(sender).wait();
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(receiver.collect().wait(), Ok(vec![
+        RetryEvent::AttemptStarted{attempt: 1},
+        RetryEvent::AttemptFailed{err: 1, attempt: 1},
+        RetryEvent::Sleeping{delay: Duration::from_millis(1)},
+        RetryEvent::AttemptStarted{attempt: 2},
+        RetryEvent::Succeeded{attempts: 2}
+    ]));
+}
+
+#[test]
+fn sends_no_sleeping_events_for_immediate_retries() {
+    use futures::Stream;
+    use futures::sync::mpsc;
+    use super::RetryEvent;
+    use super::strategy::NoDelay;
+    let (sender, receiver) = mpsc::unbounded();
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(1), || Err::<(), u64>(42)).send_events(sender).wait();
+
+    assert!(res.is_err());
+    assert_eq!(receiver.collect().wait(), Ok(vec![
+        RetryEvent::AttemptStarted{attempt: 1},
+        RetryEvent::AttemptFailed{err: 42, attempt: 1},
+        RetryEvent::AttemptStarted{attempt: 2},
+        RetryEvent::AttemptFailed{err: 42, attempt: 2},
+        RetryEvent::Exhausted{attempts: 2}
+    ]));
+}
+
+#[test]
+fn exposes_the_live_state() {
+    use super::strategy::FixedInterval;
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_secs(10)).take(1), || Err::<(), ()>(()));
+    let watch = future.watch();
+    assert_eq!(watch.status().attempt(), 0);
+
+    let polled = futures::future::lazy(|| Ok::<_, ()>(future.poll())).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    let status = watch.status();
+    assert_eq!(status.attempt(), 1);
+    assert!(status.next_attempt().expect("no next attempt") > Instant::now() + Duration::from_secs(5));
+    assert!(!status.is_finished());
+}
+
+#[test]
+fn notifies_watches_until_the_future_finished() {
+    use futures::Stream;
+    use super::RetryOutcome;
+    use super::strategy::NoDelay;
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(1), || Err::<(), ()>(()));
+    let watch = future.watch();
+    let statuses = std::thread::spawn(move || watch.collect().wait().unwrap());
+
+    assert!(future.wait().is_err());
+    let statuses = statuses.join().unwrap();
+    let last = statuses.last().expect("no status was yielded");
+    assert_eq!(last.attempt(), 2);
+    assert_eq!(last.outcome(), Some(RetryOutcome::Failed));
+
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(1), || Ok::<(), ()>(()));
+    let watch = future.watch();
+    drop(future);
+    assert_eq!(watch.status().outcome(), Some(RetryOutcome::Dropped));
+}
+
+#[test]
+fn prepares_each_retry() {
+    use std::sync::{Arc, Mutex};
+    use super::strategy::FixedInterval;
+    let prepared = Arc::new(Mutex::new(Vec::new()));
+    let recorded = prepared.clone();
+    let mut calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(5), || {
+        calls += 1;
+        if calls < 3 { Err(calls) } else { Ok(calls) }
+    }).before_retry(move |attempt| {
+        recorded.lock().unwrap().push(attempt);
+        Ok(())
+    }).wait();
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(*prepared.lock().unwrap(), vec![2, 3]);
+}
+
+#[test]
+fn aborts_when_the_preparation_fails() {
+    use super::strategy::FixedInterval;
+    let mut calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(5), || {
+        calls += 1;
+        Err::<(), _>("attempt failed")
+    }).before_retry(|_| Err("token refresh failed")).wait();
+
+    assert_eq!(res, Err(RetryError::PreparationError("token refresh failed")));
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn retries_attempts_which_time_out() {
+    use futures::future::{self, Either};
+    use super::strategy::FixedInterval;
+    let mut calls = 0;
+    let res = RetryFuture::spawn_with_timeout(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(2), Duration::from_millis(10), || {
+        calls += 1;
+        if calls < 3 { Either::A(future::empty()) } else { Either::B(future::ok::<_, ()>(calls)) }
+    }).wait();
+
+    assert_eq!(res, Ok(3));
+}
+
+#[test]
+fn fails_attempts_once_sleeping_until_the_timeout_failed() {
+    use futures::future::{self, FutureResult};
+    use super::strategy::NoDelay;
+
+    #[derive(Clone)]
+    struct BrokenSleep;
+
+    impl Sleep for BrokenSleep {
+        type Future = FutureResult<(), &'static str>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            future::err("too long")
+        }
+    }
+
+    let res = RetryFuture::spawn_with_timeout(BrokenSleep, NoDelay.take(1), Duration::from_secs(600), future::empty::<(), ()>).wait();
+    match res {
+        Err(RetryError::RetriesExhausted{last_error: TimeoutError::TimerError("too long"), attempts: 2, ..}) => {},
+        other => panic!("attempts were not aborted: {:?}", other)
+    }
+}
+
+#[test]
+fn aborts_attempts_at_the_deadline() {
+    use futures::future;
+    use super::DeadlineError;
+    use super::strategy::FixedInterval;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_millis(1)), future::empty::<(), ()>)
+        .with_deadline(Duration::from_millis(10))
+        .wait();
+
+    assert_eq!(res, Err(DeadlineError::DeadlineExceeded(Duration::from_millis(10))));
+}
+
+#[test]
+fn aborts_once_sleeping_until_the_deadline_failed() {
+    use futures::future::{self, FutureResult};
+    use super::DeadlineError;
+    use super::strategy::NoDelay;
+
+    struct BrokenSleep;
+
+    impl Sleep for BrokenSleep {
+        type Future = FutureResult<(), &'static str>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            future::err("too long")
+        }
+    }
+
+    let res = RetryFuture::spawn(BrokenSleep, NoDelay, future::empty::<(), ()>)
+        .with_deadline(Duration::from_secs(600))
+        .wait();
+
+    assert_eq!(res, Err(DeadlineError::TimerError("too long")));
+}
+
+#[test]
+fn distinguishes_cancellation_from_exhaustion() {
+    use futures::future::{self, Empty};
+    use futures::sync::oneshot;
+    use super::CancelError;
+    use super::strategy::FixedInterval;
+
+    struct EndlessSleep;
+
+    impl Sleep for EndlessSleep {
+        type Future = Empty<(), ()>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            future::empty()
+        }
+    }
+
+    let (cancel, cancelled) = oneshot::channel();
+    let mut cancel = Some(cancel);
+    let res = RetryFuture::spawn(EndlessSleep, FixedInterval::new(Duration::from_secs(60)), move || {
+        if let Some(cancel) = cancel.take() {
+            let _ = cancel.send(());
+        }
+        Err::<(), ()>(())
+    }).cancel_on(cancelled).wait();
+
+    assert_eq!(res, Err(CancelError::Cancelled));
+}
+
+#[test]
+fn aborts_via_the_handle() {
+    use std::thread;
+    use super::CancelError;
+    use super::strategy::FixedInterval;
+    let (future, handle) = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_secs(1)), || Err::<(), ()>(()))
+        .with_handle();
+    let aborted = thread::spawn(move || future.wait());
+
+    while handle.attempt() == 0 {
+        thread::yield_now();
+    }
+    handle.abort();
+
+    assert_eq!(aborted.join().unwrap(), Err(CancelError::Cancelled));
+    assert!(handle.is_aborted());
+}
+
+#[test]
+fn holds_attempts_while_paused() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use super::strategy::NoDelay;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    let (future, handle) = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(3), move || {
+        if counted.fetch_add(1, Ordering::SeqCst) < 3 { Err(()) } else { Ok(()) }
+    }).with_handle();
+    handle.pause();
+    let retried = thread::spawn(move || future.wait());
+
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    handle.resume();
+
+    assert_eq!(retried.join().unwrap(), Ok(()));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn exposes_progress_while_sleeping() {
+    use super::strategy::FixedInterval;
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), FixedInterval::new(Duration::from_secs(10)).take(1), || Err::<(), ()>(()));
+    assert_eq!((future.attempts(), future.is_sleeping(), future.next_delay_hint()), (0, false, None));
+
+    let polled = futures::future::lazy(|| Ok::<_, ()>(future.poll())).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    assert_eq!(future.attempts(), 1);
+    assert!(future.is_sleeping());
+    assert!(future.next_delay_hint().expect("no delay hint") > Duration::from_secs(5));
+}
+
+#[test]
+fn reclaims_the_action_once_exhausted() {
+    use super::strategy::NoDelay;
+    struct Client {
+        warmed: usize
+    }
+
+    impl Action for Client {
+        type Item = ();
+        type Error = ();
+        type Future = FutureResult<(), ()>;
+
+        fn run(&mut self) -> Self::Future {
+            self.warmed += 1;
+            futures::future::err(())
+        }
+    }
+
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(2), Client{warmed: 0}).reclaim_action().wait();
+    match res {
+        Err((err, client)) => {
+            assert_eq!(err.into_inner(), Ok(()));
+            assert_eq!(client.warmed, 3);
+        },
+        Ok(_) => panic!("retries were not exhausted")
+    }
+}
+
+#[test]
+fn formats_the_state_for_debugging() {
+    use super::strategy::FixedInterval;
+    let future = RetryFuture::spawn_named(tokio_timer::Timer::default(), "debug", FixedInterval::new(Duration::from_secs(1)).take(1), || Ok::<(), ()>(()));
+    let formatted = format!("{:?}", future);
+    assert!(formatted.starts_with(r#"RetryFuture { name: Some("debug"), state: Pending, attempt: 0, strategy: Take"#), "{}", formatted);
+}
+
+#[test]
+fn retries_synchronous_failures_without_recursing() {
+    use super::strategy::NoDelay;
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(100_000), || {
+        num_calls += 1;
+        Err::<(), u64>(42)
+    }).wait();
+
+    assert!(res.is_err());
+    assert_eq!(num_calls, 100_001);
+}
+
+#[test]
+fn yields_between_immediate_retries() {
+    use super::strategy::NoDelay;
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(10), || Err::<(), u64>(42)).yield_after(3);
+
+    let polled = futures::future::lazy(|| Ok::<_, ()>(future.poll())).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    assert_eq!(future.attempts(), 2);
+    assert_eq!(future.wait(), Err(RetryError::RetriesExhausted{last_error: 42, attempts: 11, total_elapsed: Duration::ZERO, recent_errors: vec![], attempts_exhausted: false}));
+}
+
+#[test]
+fn passes_the_context_to_the_action() {
+    use super::strategy::NoDelay;
+    let res = RetryFuture::spawn_with_context(tokio_timer::Timer::default(), NoDelay.take(5), |context: &RetryContext| {
+        if context.attempt() < 3 { Err(context.attempt()) } else { Ok(context.attempt()) }
+    }).wait();
+
+    assert_eq!(res, Ok(3));
+}
+
+#[test]
+fn passes_the_context_of_the_sequence_through_adapters() {
+    use super::ActionWithContext;
+    use std::time::Duration;
+    use super::strategy::NoDelay;
+    let mut attempts = Vec::new();
+    let action = ActionWithContext::new(|context: &RetryContext| {
+        attempts.push(context.attempt());
+        Err::<(), u64>(42)
+    });
+    let res = RetryFuture::spawn_with_timeout(tokio_timer::Timer::default(), NoDelay.take(2), Duration::from_secs(10), action).wait();
+
+    assert!(res.is_err());
+    assert_eq!(attempts, vec![1, 2, 3]);
+}
+
+#[test]
+fn passes_the_previous_error_to_the_action() {
+    use super::strategy::NoDelay;
+    let mut seen = Vec::new();
+    let res = RetryFuture::spawn_with_previous_error(tokio_timer::Timer::default(), NoDelay.take(2), |previous: Option<&u64>| {
+        seen.push(previous.cloned());
+        Err::<(), u64>(seen.len() as u64)
+    }).wait();
+
+    assert!(res.is_err());
+    assert_eq!(seen, vec![None, Some(1), Some(2)]);
+}
+
+#[cfg(feature = "tokio_timer")]
+#[test]
+fn sleeps_on_the_default_timer() {
+    use std::time::Duration;
+    use super::strategy::FixedInterval;
+    let mut attempts = 0;
+    let res = RetryFuture::spawn_default(FixedInterval::new(Duration::from_millis(200)).take(1), || {
+        attempts += 1;
+        if attempts < 2 { Err(()) } else { Ok(attempts) }
+    }).wait();
+
+    assert_eq!(res, Ok(2));
+}
+
+#[cfg(feature = "tokio_timer")]
+#[test]
+fn accepts_long_sleeps_on_the_default_timer() {
+    use futures::future::lazy;
+    use std::time::Duration;
+    let mut sleep = DefaultTimer.sleep(Duration::from_secs(600));
+    let polled = lazy(|| Ok::<_, ()>(sleep.poll())).wait().unwrap();
+    assert_eq!(polled, Ok(Async::NotReady));
+}
+
+#[test]
+fn falls_back_to_another_retry_sequence() {
+    use super::strategy::NoDelay;
+    use super::FallbackError;
+    let timer = tokio_timer::Timer::default();
+    let res = RetryFuture::spawn(timer.clone(), NoDelay.take(2), || Err::<u64, u64>(1))
+        .or_else_try(RetryFuture::spawn(timer.clone(), NoDelay.take(1), || Ok::<u64, u64>(42)))
+        .wait();
+    assert_eq!(res, Ok(42));
+
+    let res = RetryFuture::spawn(timer.clone(), NoDelay.take(2), || Err::<u64, u64>(1))
+        .or_else_try(RetryFuture::spawn(timer, NoDelay.take(1), || Err::<u64, u64>(2)))
+        .wait();
+    match res {
+        Err(FallbackError::Fallback{primary, fallback}) => {
+            assert_eq!(primary.operation_error(), Some(&1));
+            assert_eq!(fallback.operation_error(), Some(&2));
+        },
+        _ => panic!("the fallback did not fail")
+    }
+}
+
+#[test]
+fn serves_a_fallback_value_after_exhausting_retries() {
+    use std::cell::Cell;
+    use super::strategy::NoDelay;
+    let exhausted = Cell::new(false);
+    struct OnExhausted<'a>(&'a Cell<bool>);
+    impl<'a> RetryHook<u64> for OnExhausted<'a> {
+        fn on_exhausted(&mut self, _: &u64, _: usize) {
+            self.0.set(true);
+        }
+    }
+
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(2), || Err::<u64, u64>(1))
+        .with_hook(OnExhausted(&exhausted))
+        .unwrap_or_else_after_retries(|err| err.operation_error().cloned().unwrap_or(0) + 41)
+        .wait();
+
+    assert_eq!(res, Ok(42));
+    assert!(exhausted.get());
+}
+
+#[test]
+fn bounds_retries_by_a_shared_budget() {
+    use super::strategy::{NoDelay, RetryBudget};
+    let timer = tokio_timer::Timer::default();
+    let budget = RetryBudget::new(3, 0.1);
+    let mut attempts = 0;
+    let res = RetryFuture::spawn_with_budget(timer.clone(), budget.clone(), NoDelay.take(2), || {
+        attempts += 1;
+        Err::<(), ()>(())
+    }).wait();
+    assert!(res.is_err());
+    assert_eq!(attempts, 3);
+
+    let mut attempts = 0;
+    let _ = RetryFuture::spawn_with_budget(timer, budget.clone(), NoDelay.take(2), || {
+        attempts += 1;
+        Err::<(), ()>(())
+    }).wait();
+    assert_eq!(attempts, 2);
+    assert_eq!(budget.available(), 0);
+}
+
+#[test]
+fn waits_for_the_limit_of_its_key() {
+    use futures::future::lazy;
+    use super::KeyedLimiter;
+    use super::strategy::NoDelay;
+    let limiter = KeyedLimiter::new(1);
+    let mut blocker = limiter.limit("host");
+    assert!(blocker.poll_acquire());
+
+    let mut attempts = 0;
+    let mut future = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(1), || {
+        attempts += 1;
+        Err::<(), ()>(())
+    }).limit_retries(&limiter, "host");
+
+    let polled = lazy(|| Ok::<_, ()>(future.poll())).wait().unwrap();
+    assert_eq!(polled, Ok(Async::NotReady));
+    assert_eq!(future.attempts(), 1);
+
+    drop(blocker);
+    assert!(future.wait().is_err());
+    assert_eq!(attempts, 2);
+    assert_eq!(limiter.retrying(&"host"), 0);
+}
+
+#[test]
+fn fails_fast_once_the_limit_of_its_key_is_reached() {
+    use super::KeyedLimiter;
+    use super::strategy::NoDelay;
+    let limiter = KeyedLimiter::new(1).fail_fast();
+    let mut blocker = limiter.limit("host");
+    assert!(blocker.poll_acquire());
+
+    let mut attempts = 0;
+    let res = RetryFuture::spawn(tokio_timer::Timer::default(), NoDelay.take(5), || {
+        attempts += 1;
+        Err::<(), ()>(())
+    }).limit_retries(&limiter, "host").wait();
+
+    assert_eq!(res, Err(RetryError::Rejected{last_error: (), attempts: 1, total_elapsed: Duration::ZERO}));
+    assert_eq!(attempts, 1);
+}
+
+#[test]
+fn retries_attempts_which_panic() {
+    use super::strategy::NoDelay;
+    let mut attempts = 0;
+    let res = RetryFuture::spawn_catch_unwind(tokio_timer::Timer::default(), NoDelay.take(2), || {
+        attempts += 1;
+        if attempts < 2 {
+            panic!("flaky");
+        }
+        Ok::<u64, ()>(42)
+    }).wait();
+
+    assert_eq!(res.ok(), Some(42));
+}
+
+#[test]
+fn retries_attempts_spawned_onto_an_executor() {
+    use futures::future::{ExecuteError, Executor};
+    use std::thread;
+    use super::strategy::NoDelay;
+
+    struct ThreadPerAttempt;
+
+    impl<F: Future<Item=(), Error=()> + Send + 'static> Executor<F> for ThreadPerAttempt {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            thread::spawn(move || future.wait());
+            Ok(())
+        }
+    }
+
+    let mut attempts = 0;
+    let res = RetryFuture::spawn_isolated(tokio_timer::Timer::default(), ThreadPerAttempt, NoDelay.take(2), || {
+        attempts += 1;
+        if attempts < 3 { Err(attempts) } else { Ok(attempts) }
+    }).wait();
+
+    assert_eq!(res, Ok(3));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializes_retry_errors() {
+    extern crate serde_json;
+    let err: RetryError<&str, ()> = RetryError::OperationError("refused");
+    assert_eq!(serde_json::to_string(&err).unwrap(), r#"{"OperationError":"refused"}"#);
+
+    let err: RetryError<&str, ()> = RetryError::RetriesExhausted{
+        last_error: "refused",
+        attempts: 3,
+        total_elapsed: Duration::from_secs(2),
+        recent_errors: vec!["reset", "refused"],
+        attempts_exhausted: true
+    };
+    assert_eq!(
+        serde_json::to_string(&err).unwrap(),
+        r#"{"RetriesExhausted":{"last_error":"refused","attempts":3,"total_elapsed":{"secs":2,"nanos":0},"recent_errors":["reset","refused"],"attempts_exhausted":true}}"#
+    );
+}
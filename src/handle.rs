@@ -0,0 +1,106 @@
+use futures::{Async, Future, Poll};
+use futures::task::AtomicTask;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::{RetryStatus, RetryWatch};
+
+struct Abort {
+    aborted: AtomicBool,
+    task: AtomicTask
+}
+
+/// Shared state of a pausable `RetryFuture`.
+pub(crate) struct Pause {
+    paused: AtomicBool,
+    task: AtomicTask
+}
+
+impl Pause {
+    /// Returns whether attempts may be started, or else arranges for the
+    /// current task to be notified once they may.
+    pub(crate) fn poll_resumed(&self) -> bool {
+        self.task.register();
+        !self.paused.load(Ordering::SeqCst)
+    }
+}
+
+/// Handle to a `RetryFuture` driven elsewhere, which allows aborting, pausing and
+/// resuming it, and querying its current attempt from another task.
+///
+/// This `struct` is created by the `with_handle` method on `RetryFuture`.
+#[derive(Clone)]
+pub struct RetryHandle {
+    abort: Arc<Abort>,
+    pause: Arc<Pause>,
+    watch: RetryWatch
+}
+
+impl RetryHandle {
+    /// Aborts the retry sequence, which then fails with `CancelError::Cancelled`.
+    pub fn abort(&self) {
+        self.abort.aborted.store(true, Ordering::SeqCst);
+        self.abort.task.notify();
+    }
+
+    /// Returns whether the retry sequence has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.abort.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Pauses the retry sequence: an attempt in progress is completed, but no
+    /// further attempts are started until it is resumed.
+    ///
+    /// This allows e.g. to stop hitting a database during a failover, without
+    /// losing the state of the retry sequence.
+    pub fn pause(&self) {
+        self.pause.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resumes a paused retry sequence.
+    pub fn resume(&self) {
+        self.pause.paused.store(false, Ordering::SeqCst);
+        self.pause.task.notify();
+    }
+
+    /// Returns whether the retry sequence is paused.
+    pub fn is_paused(&self) -> bool {
+        self.pause.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of the current or most recent attempt, counting from 1.
+    pub fn attempt(&self) -> usize {
+        self.watch.status().attempt()
+    }
+
+    /// Returns the current state of the retry sequence.
+    pub fn status(&self) -> RetryStatus {
+        self.watch.status()
+    }
+}
+
+/// Future which completes once the corresponding [`RetryHandle`](struct.RetryHandle.html) is aborted.
+pub struct AbortSignal {
+    abort: Arc<Abort>
+}
+
+impl Future for AbortSignal {
+    type Item = ();
+    type Error = Infallible;
+
+    fn poll(&mut self) -> Poll<(), Infallible> {
+        self.abort.task.register();
+        if self.abort.aborted.load(Ordering::SeqCst) {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+pub(crate) fn pair(watch: RetryWatch) -> (AbortSignal, Arc<Pause>, RetryHandle) {
+    let abort = Arc::new(Abort{aborted: AtomicBool::new(false), task: AtomicTask::new()});
+    let pause = Arc::new(Pause{paused: AtomicBool::new(false), task: AtomicTask::new()});
+    (AbortSignal{abort: abort.clone()}, pause.clone(), RetryHandle{abort, pause, watch})
+}
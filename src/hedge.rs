@@ -0,0 +1,232 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryableError, Sleep};
+use super::condition::RetryContext;
+use super::expiry::Expiry;
+
+/// Represents the errors of an action whose attempts are hedged.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum HedgeError<E, TE> {
+    /// Sleeping until the speculative attempt was due failed, e.g. because the delay exceeds
+    /// the `max_timeout` of the timer, so that the attempt was aborted rather than left unhedged.
+    TimerError(TE),
+    /// The attempt failed.
+    Failed(E)
+}
+
+impl<E: fmt::Display, TE: fmt::Display> fmt::Display for HedgeError<E, TE> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            HedgeError::TimerError(ref err) => write!(formatter, "sleeping until the hedge failed: {}", err),
+            HedgeError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error, TE: Error> Error for HedgeError<E, TE> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            HedgeError::TimerError(ref err) => Some(err),
+            HedgeError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Timer errors are not retryable, as the timer is unlikely to accept the same delay later;
+/// other errors only if they report to be.
+impl<E: RetryableError, TE> RetryableError for HedgeError<E, TE> {
+    fn is_retryable(&self) -> bool {
+        match *self {
+            HedgeError::TimerError(_) => false,
+            HedgeError::Failed(ref err) => err.is_retryable()
+        }
+    }
+}
+
+/// An action adapter which hedges slow attempts: once an attempt has not completed
+/// within a delay, a second, speculative attempt is started in parallel, and whichever
+/// of them succeeds first completes the attempt, dropping the other.
+///
+/// The speculative attempt is run by a clone of the action, taken after the original attempt
+/// started, so that e.g. a [`FailoverAction`](struct.FailoverAction.html) hedges against its
+/// next target. Both runs are passed the context of the attempt. The attempt only fails once
+/// both of its runs did, with the latest error, or with `HedgeError::TimerError` should
+/// sleeping until the hedge fail.
+///
+/// This `struct` is created by `RetryFuture::spawn_hedged`.
+pub struct Hedge<A, S> {
+    action: A,
+    sleep: S,
+    delay: Duration
+}
+
+impl<A, S> Hedge<A, S> {
+    pub(crate) fn new(action: A, sleep: S, delay: Duration) -> Hedge<A, S> {
+        Hedge{action, sleep, delay}
+    }
+}
+
+impl<A: Action + Clone, S: Sleep> Hedge<A, S> {
+    fn hedge(&mut self, primary: A::Future, context: Option<RetryContext>) -> HedgeFuture<A, S::Future> {
+        HedgeFuture{
+            primary: Some(primary),
+            hedge: None,
+            spare: Some(self.action.clone()),
+            context,
+            hedged: false,
+            timer: Expiry::new(self.sleep.sleep(self.delay))
+        }
+    }
+}
+
+impl<A: Action + Clone, S: Sleep> Action for Hedge<A, S> {
+    type Item = A::Item;
+    type Error = HedgeError<A::Error, <S::Future as Future>::Error>;
+    type Future = HedgeFuture<A, S::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        let primary = self.action.run();
+        self.hedge(primary, None)
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        let primary = match *previous {
+            HedgeError::TimerError(_) => self.action.run(),
+            HedgeError::Failed(ref err) => self.action.run_after(err)
+        };
+        self.hedge(primary, None)
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            HedgeError::Failed(ref err) => Some(err),
+            _ => None
+        });
+        let primary = self.action.run_with_context(previous, context);
+        self.hedge(primary, Some(*context))
+    }
+}
+
+/// Future of a single attempt at a [`Hedge`](struct.Hedge.html) action.
+pub struct HedgeFuture<A: Action, T> {
+    primary: Option<A::Future>,
+    hedge: Option<A::Future>,
+    spare: Option<A>,
+    context: Option<RetryContext>,
+    hedged: bool,
+    timer: Expiry<T>
+}
+
+impl<A: Action, T> HedgeFuture<A, T> {
+    /// Returns whether the speculative attempt has been started.
+    pub fn is_hedged(&self) -> bool {
+        self.hedged
+    }
+}
+
+impl<A: Action, T: Future> Future for HedgeFuture<A, T> {
+    type Item = A::Item;
+    type Error = HedgeError<A::Error, T::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let primary = match self.primary {
+            Some(ref mut primary) => primary.poll(),
+            None => Ok(Async::NotReady)
+        };
+        match primary {
+            Ok(Async::Ready(item)) => return Ok(Async::Ready(item)),
+            Ok(Async::NotReady) => {},
+            // Before the hedge has started, failures are left to be retried as usual.
+            Err(err) if self.hedge.is_none() => return Err(HedgeError::Failed(err)),
+            Err(_) => self.primary = None
+        }
+
+        if self.timer.poll_expired().map_err(HedgeError::TimerError)? {
+            let context = self.context;
+            self.hedge = self.spare.take().map(|mut spare| match context {
+                Some(ref context) => spare.run_with_context(None, context),
+                None => spare.run()
+            });
+            self.hedged = true;
+        }
+
+        let hedge = match self.hedge {
+            Some(ref mut hedge) => hedge.poll(),
+            None => Ok(Async::NotReady)
+        };
+        match hedge {
+            Ok(Async::Ready(item)) => Ok(Async::Ready(item)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(err) if self.primary.is_none() => Err(HedgeError::Failed(err)),
+            Err(_) => {
+                self.hedge = None;
+                Ok(Async::NotReady)
+            }
+        }
+    }
+}
+
+#[test]
+fn completes_with_the_hedge_if_it_is_faster() {
+    use futures::future::{empty, ok};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_timer::Timer;
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let action = move || -> Box<dyn Future<Item=usize, Error=()> + Send> {
+        match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => Box::new(empty()),
+            run => Box::new(ok(run))
+        }
+    };
+    let mut hedge = Hedge::new(action, Timer::default(), Duration::from_millis(10));
+
+    assert_eq!(hedge.run().wait(), Ok(1));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn fails_without_hedging_before_the_delay() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tokio_timer::Timer;
+
+    let runs = Rc::new(Cell::new(0));
+    let counter = runs.clone();
+    let action = move || {
+        counter.set(counter.get() + 1);
+        Err::<(), ()>(())
+    };
+    let mut hedge = Hedge::new(action, Timer::default(), Duration::from_secs(10));
+
+    assert_eq!(hedge.run().wait(), Err(HedgeError::Failed(())));
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+fn fails_once_sleeping_until_the_hedge_failed() {
+    use futures::future::{err, lazy, empty, Empty, FutureResult};
+
+    struct BrokenSleep;
+
+    impl Sleep for BrokenSleep {
+        type Future = FutureResult<(), &'static str>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            err("too long")
+        }
+    }
+
+    let mut hedge = Hedge::new(empty::<(), ()> as fn() -> Empty<(), ()>, BrokenSleep, Duration::ZERO);
+    let mut attempt = hedge.run();
+    let polled = lazy(|| Ok::<_, ()>(attempt.poll())).wait().unwrap();
+    assert_eq!(polled, Err(HedgeError::TimerError("too long")));
+    assert!(!attempt.is_hedged());
+}
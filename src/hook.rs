@@ -0,0 +1,158 @@
+use futures::sync::mpsc::UnboundedSender;
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+#[cfg(feature = "tracing")]
+use tracing;
+
+/// Hooks invoked by `RetryFuture` as it makes progress.
+///
+/// All methods default to doing nothing, so that implementations only
+/// need to provide the ones they are interested in.
+pub trait RetryHook<E> {
+    /// Called when the given attempt is started, counting from 1.
+    fn on_attempt(&mut self, attempt: usize) {
+        let _ = attempt;
+    }
+
+    /// Called when an attempt failed with the given error, before it is decided
+    /// whether to retry it.
+    fn on_failure(&mut self, error: &E, attempt: usize) {
+        let _ = (error, attempt);
+    }
+
+    /// Called after an attempt failed with the given error, before waiting
+    /// for `delay` and running the next attempt.
+    fn on_retry(&mut self, error: &E, attempt: usize, delay: Duration) {
+        let _ = (error, attempt, delay);
+    }
+
+    /// Called when the given attempt succeeded.
+    fn on_success(&mut self, attempt: usize) {
+        let _ = attempt;
+    }
+
+    /// Called when the strategy ran out after the given attempt failed with the given error.
+    fn on_exhausted(&mut self, error: &E, attempt: usize) {
+        let _ = (error, attempt);
+    }
+}
+
+/// A hook which does nothing.
+///
+/// This is the hook used by `RetryFuture` unless configured otherwise.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoHook;
+
+impl<E> RetryHook<E> for NoHook {}
+
+impl<E, A: RetryHook<E>, B: RetryHook<E>> RetryHook<E> for (A, B) {
+    fn on_attempt(&mut self, attempt: usize) {
+        self.0.on_attempt(attempt);
+        self.1.on_attempt(attempt);
+    }
+
+    fn on_failure(&mut self, error: &E, attempt: usize) {
+        self.0.on_failure(error, attempt);
+        self.1.on_failure(error, attempt);
+    }
+
+    fn on_retry(&mut self, error: &E, attempt: usize, delay: Duration) {
+        self.0.on_retry(error, attempt, delay);
+        self.1.on_retry(error, attempt, delay);
+    }
+
+    fn on_success(&mut self, attempt: usize) {
+        self.0.on_success(attempt);
+        self.1.on_success(attempt);
+    }
+
+    fn on_exhausted(&mut self, error: &E, attempt: usize) {
+        self.0.on_exhausted(error, attempt);
+        self.1.on_exhausted(error, attempt);
+    }
+}
+
+/// A hook calling a function before each retry.
+///
+/// This `struct` is created by the `on_retry` method on `RetryFuture`.
+#[derive(Clone)]
+pub struct OnRetry<F> {
+    f: F
+}
+
+impl<F> OnRetry<F> {
+    pub(crate) fn new(f: F) -> OnRetry<F> {
+        OnRetry{f}
+    }
+}
+
+impl<E, F: FnMut(&E, usize, Duration)> RetryHook<E> for OnRetry<F> {
+    fn on_retry(&mut self, error: &E, attempt: usize, delay: Duration) {
+        (self.f)(error, attempt, delay)
+    }
+}
+
+/// An event in the lifecycle of a `RetryFuture`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RetryEvent<E> {
+    /// The given attempt was started, counting from 1.
+    AttemptStarted{attempt: usize},
+    /// The given attempt failed with an error.
+    AttemptFailed{err: E, attempt: usize},
+    /// The next attempt will be started after the given, non-zero delay.
+    Sleeping{delay: Duration},
+    /// The given attempt succeeded.
+    Succeeded{attempts: usize},
+    /// The strategy ran out after the given number of attempts.
+    Exhausted{attempts: usize}
+}
+
+/// The sending end of a channel of `RetryEvent`s.
+pub type EventSender<E> = UnboundedSender<RetryEvent<E>>;
+
+/// Reports the lifecycle of a `RetryFuture` as `RetryEvent`s.
+///
+/// Events are dropped once the receiving end of the channel has been dropped.
+impl<E: Clone> RetryHook<E> for EventSender<E> {
+    fn on_attempt(&mut self, attempt: usize) {
+        let _ = self.unbounded_send(RetryEvent::AttemptStarted{attempt});
+    }
+
+    fn on_failure(&mut self, error: &E, attempt: usize) {
+        let _ = self.unbounded_send(RetryEvent::AttemptFailed{err: error.clone(), attempt});
+    }
+
+    fn on_retry(&mut self, _: &E, _: usize, delay: Duration) {
+        // Immediate retries never sleep.
+        if delay > Duration::ZERO {
+            let _ = self.unbounded_send(RetryEvent::Sleeping{delay});
+        }
+    }
+
+    fn on_success(&mut self, attempt: usize) {
+        let _ = self.unbounded_send(RetryEvent::Succeeded{attempts: attempt});
+    }
+
+    fn on_exhausted(&mut self, _: &E, attempt: usize) {
+        let _ = self.unbounded_send(RetryEvent::Exhausted{attempts: attempt});
+    }
+}
+
+/// A hook emitting a `tracing` event with the error of each failed attempt before it is retried.
+///
+/// This `struct` is created by the `trace_errors` method on `RetryFuture`.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraceErrors;
+
+#[cfg(feature = "tracing")]
+impl<E: fmt::Display> RetryHook<E> for TraceErrors {
+    fn on_retry(&mut self, error: &E, attempt: usize, delay: Duration) {
+        tracing::warn!(attempt, delay = ?delay, error = %error, "retrying after error");
+    }
+}
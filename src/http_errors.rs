@@ -0,0 +1,30 @@
+use http::StatusCode;
+use hyper;
+
+use super::RetryableError;
+
+/// Responses with status `408 Request Timeout`, `429 Too Many Requests`
+/// or any server error are retryable, while other client errors are not.
+impl RetryableError for StatusCode {
+    fn is_retryable(&self) -> bool {
+        *self == StatusCode::REQUEST_TIMEOUT || *self == StatusCode::TOO_MANY_REQUESTS || self.is_server_error()
+    }
+}
+
+/// Errors establishing or keeping up a connection are retryable,
+/// while errors parsing messages or using the API are not.
+impl RetryableError for hyper::Error {
+    fn is_retryable(&self) -> bool {
+        self.is_connect() || self.is_closed() || self.is_canceled() || self.is_incomplete_message()
+    }
+}
+
+#[test]
+fn retries_timeouts_throttling_and_server_errors() {
+    assert!(StatusCode::REQUEST_TIMEOUT.is_retryable());
+    assert!(StatusCode::TOO_MANY_REQUESTS.is_retryable());
+    assert!(StatusCode::SERVICE_UNAVAILABLE.is_retryable());
+    assert!(!StatusCode::NOT_FOUND.is_retryable());
+    assert!(!StatusCode::UNAUTHORIZED.is_retryable());
+    assert!(!StatusCode::OK.is_retryable());
+}
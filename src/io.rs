@@ -0,0 +1,348 @@
+use futures::{task, Async, Future, IntoFuture, Poll};
+use futures::future::{self, FutureResult};
+use std::error::Error;
+use std::cmp;
+use std::io::{self, Read, Write};
+use std::mem;
+use tokio_io::{AsyncRead, AsyncWrite};
+
+use super::{Action, Sleep};
+use super::future::DEFAULT_YIELD_AFTER;
+use super::strategy::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// The handshake of connections which need none.
+type NoHandshake<T> = fn(T) -> FutureResult<T, io::Error>;
+
+enum IoState<S: Sleep, C: Action, U: IntoFuture> {
+    Disconnected,
+    Connecting(C::Future),
+    Handshaking(U::Future),
+    Connected(C::Item),
+    Sleeping(S::Future)
+}
+
+/// Returns whether the error reports a connection which was lost, and can be re-established.
+fn is_disconnect(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted | io::ErrorKind::BrokenPipe)
+}
+
+/// I/O adapter for persistent connections, such as TCP clients, which connects lazily via
+/// an action, and transparently reconnects with back-off whenever the connection is reset.
+///
+/// Reads and writes failing with `ConnectionReset`, `ConnectionAborted` or `BrokenPipe` drop
+/// the connection and are retried on a new one, other errors are returned as they are. Data
+/// written to the lost connection but not yet processed by its peer is lost, so protocols
+/// which need to, e.g. to authenticate or resubscribe, can replay a handshake on every new
+/// connection via `handshake`. Failures to connect are delayed by the strategy as well, and
+/// the strategy is reset once data has been transferred on a new connection.
+///
+/// Once the strategy gives up, the last error is returned. Like all tokio I/O objects,
+/// reads and writes must happen within a task, and fail with `WouldBlock` while reconnecting.
+///
+/// This requires the `tokio_io` feature.
+pub struct ReconnectingIo<S: Sleep, I, C: Action, H = NoHandshake<<C as Action>::Item>, U: IntoFuture = FutureResult<<C as Action>::Item, io::Error>> {
+    sleep: S,
+    strategy: I,
+    connect: C,
+    handshake: H,
+    yield_after: usize,
+    attempt: usize,
+    lost: bool,
+    reconnects: usize,
+    state: IoState<S, C, U>
+}
+
+impl<S, I, C> ReconnectingIo<S, I, C>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite {
+    /// Constructs a reconnecting I/O object, connecting via the given action.
+    pub fn new<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, connect: C) -> ReconnectingIo<S, I, C> {
+        ReconnectingIo{
+            sleep,
+            strategy: strategy.into_strategy(),
+            connect,
+            handshake: future::ok,
+            yield_after: DEFAULT_YIELD_AFTER,
+            attempt: 0,
+            lost: false,
+            reconnects: 0,
+            state: IoState::Disconnected
+        }
+    }
+
+    /// Runs the handshake with every new connection, before it is used for reads and writes.
+    ///
+    /// A failing handshake counts as a failure to connect.
+    pub fn handshake<H, U>(self, handshake: H) -> ReconnectingIo<S, I, C, H, U>
+        where H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error> {
+        ReconnectingIo{
+            sleep: self.sleep,
+            strategy: self.strategy,
+            connect: self.connect,
+            handshake,
+            yield_after: self.yield_after,
+            attempt: self.attempt,
+            lost: self.lost,
+            reconnects: self.reconnects,
+            state: IoState::Disconnected
+        }
+    }
+}
+
+impl<S, I, C, H, U> ReconnectingIo<S, I, C, H, U>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite,
+          H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error>, <S::Future as Future>::Error: Into<Box<dyn Error + Send + Sync>> {
+    /// Yields to the executor after the given number of consecutive state transitions
+    /// within a single read or write, which defaults to 64, see `RetryFuture::yield_after`.
+    pub fn yield_after(mut self, transitions: usize) -> ReconnectingIo<S, I, C, H, U> {
+        self.yield_after = cmp::max(transitions, 1);
+        self
+    }
+
+    /// Returns whether a connection is currently established.
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, IoState::Connected(_))
+    }
+
+    /// Returns the number of times the connection was re-established after it was lost.
+    pub fn reconnects(&self) -> usize {
+        self.reconnects
+    }
+
+    /// Returns the current connection, if any.
+    pub fn get_ref(&self) -> Option<&C::Item> {
+        match self.state {
+            IoState::Connected(ref connection) => Some(connection),
+            _ => None
+        }
+    }
+
+    fn retry(&mut self, err: io::Error) -> io::Result<()> {
+        self.attempt += 1;
+        match self.strategy.delay(self.attempt, &err) {
+            Some(delay) => {
+                self.state = IoState::Sleeping(self.sleep.sleep(delay));
+                Ok(())
+            },
+            None => Err(err)
+        }
+    }
+
+    /// Drives the state until connected, returning the connection, as long as
+    /// the budget of transitions lasts.
+    fn poll_connected(&mut self, budget: &mut usize) -> Poll<&mut C::Item, io::Error> {
+        loop {
+            if *budget == 0 {
+                task::current().notify();
+                return Ok(Async::NotReady);
+            }
+            *budget -= 1;
+            self.state = match mem::replace(&mut self.state, IoState::Disconnected) {
+                IoState::Disconnected => IoState::Connecting(self.connect.run()),
+                IoState::Connecting(mut future) => match future.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = IoState::Connecting(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(connection)) => IoState::Handshaking((self.handshake)(connection).into_future()),
+                    Err(err) => {
+                        self.retry(err)?;
+                        continue;
+                    }
+                },
+                IoState::Handshaking(mut future) => match future.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = IoState::Handshaking(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(connection)) => {
+                        if self.lost {
+                            self.lost = false;
+                            self.reconnects += 1;
+                        }
+                        IoState::Connected(connection)
+                    },
+                    Err(err) => {
+                        self.retry(err)?;
+                        continue;
+                    }
+                },
+                IoState::Connected(connection) => {
+                    self.state = IoState::Connected(connection);
+                    break;
+                },
+                IoState::Sleeping(mut future) => match future.poll().map_err(io::Error::other)? {
+                    Async::NotReady => {
+                        self.state = IoState::Sleeping(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Async::Ready(_) => IoState::Connecting(self.connect.run())
+                }
+            };
+        }
+        match self.state {
+            IoState::Connected(ref mut connection) => Ok(Async::Ready(connection)),
+            _ => unreachable!()
+        }
+    }
+
+    /// Runs the I/O operation on the connection, reconnecting as long as it fails with
+    /// a lost connection.
+    fn with_connection<T, F>(&mut self, mut op: F) -> io::Result<T> where F: FnMut(&mut C::Item) -> io::Result<T> {
+        let mut budget = self.yield_after;
+        loop {
+            let res = match self.poll_connected(&mut budget)? {
+                Async::Ready(connection) => op(connection),
+                Async::NotReady => return Err(io::ErrorKind::WouldBlock.into())
+            };
+            match res {
+                Ok(value) => {
+                    if self.attempt > 0 {
+                        self.strategy.reset();
+                        self.attempt = 0;
+                    }
+                    return Ok(value);
+                },
+                Err(err) => {
+                    if !is_disconnect(&err) {
+                        return Err(err);
+                    }
+                    self.lost = true;
+                    self.retry(err)?;
+                }
+            }
+        }
+    }
+}
+
+impl<S, I, C, H, U> Read for ReconnectingIo<S, I, C, H, U>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite,
+          H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error>, <S::Future as Future>::Error: Into<Box<dyn Error + Send + Sync>> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_connection(|connection| connection.read(buf))
+    }
+}
+
+impl<S, I, C, H, U> Write for ReconnectingIo<S, I, C, H, U>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite,
+          H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error>, <S::Future as Future>::Error: Into<Box<dyn Error + Send + Sync>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.with_connection(|connection| connection.write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.with_connection(|connection| connection.flush())
+    }
+}
+
+impl<S, I, C, H, U> AsyncRead for ReconnectingIo<S, I, C, H, U>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite,
+          H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error>, <S::Future as Future>::Error: Into<Box<dyn Error + Send + Sync>> {}
+
+/// Shuts down the current connection, if any, without reconnecting.
+impl<S, I, C, H, U> AsyncWrite for ReconnectingIo<S, I, C, H, U>
+    where S: Sleep, I: BackoffStrategy<io::Error> + ResettableStrategy, C: Action<Error=io::Error>, C::Item: AsyncRead + AsyncWrite,
+          H: FnMut(C::Item) -> U, U: IntoFuture<Item=C::Item, Error=io::Error>, <S::Future as Future>::Error: Into<Box<dyn Error + Send + Sync>> {
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        match self.state {
+            IoState::Connected(ref mut connection) => connection.shutdown(),
+            _ => Ok(Async::Ready(()))
+        }
+    }
+}
+
+#[cfg(test)]
+struct Connection {
+    input: io::Cursor<Vec<u8>>,
+    output: std::rc::Rc<std::cell::RefCell<Vec<u8>>>
+}
+
+#[cfg(test)]
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.input.read(buf)? {
+            0 => Err(io::ErrorKind::ConnectionReset.into()),
+            n => Ok(n)
+        }
+    }
+}
+
+#[cfg(test)]
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl AsyncRead for Connection {}
+
+#[cfg(test)]
+impl AsyncWrite for Connection {
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        Ok(Async::Ready(()))
+    }
+}
+
+#[test]
+fn reconnects_and_replays_the_handshake_once_reset() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut connections = 0;
+    let mut io = ReconnectingIo::new(tokio_timer::Timer::default(), NoDelay.max_attempts(2), || {
+        connections += 1;
+        Ok(Connection{input: io::Cursor::new(vec![connections]), output: output.clone()})
+    }).handshake(|mut connection: Connection| {
+        connection.write_all(b"hello").map(|_| connection)
+    });
+
+    let mut buf = [0; 4];
+    future::lazy(|| {
+        assert_eq!(io.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 1);
+        assert_eq!(io.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 2);
+        Ok::<_, ()>(())
+    }).wait().unwrap();
+
+    assert_eq!(io.reconnects(), 1);
+    assert_eq!(*output.borrow(), b"hellohello".to_vec());
+}
+
+#[test]
+fn fails_once_reconnects_are_exhausted() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut io = ReconnectingIo::new(tokio_timer::Timer::default(), NoDelay.max_attempts(2), || {
+        Ok(Connection{input: io::Cursor::new(Vec::new()), output: output.clone()})
+    });
+
+    let err = future::lazy(|| Ok::<_, ()>(io.read(&mut [0; 4]))).wait().unwrap().err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(io.reconnects(), 1);
+}
+
+#[test]
+fn yields_between_immediate_reconnects() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::NoDelay;
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut io = ReconnectingIo::new(tokio_timer::Timer::default(), NoDelay, || {
+        Ok(Connection{input: io::Cursor::new(Vec::new()), output: output.clone()})
+    }).yield_after(8);
+
+    let err = future::lazy(|| Ok::<_, ()>(io.read(&mut [0; 4]))).wait().unwrap().err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(io.reconnects() > 0);
+}
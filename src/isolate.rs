@@ -0,0 +1,186 @@
+use futures::{Async, Future, Poll};
+use futures::future::Executor;
+use futures::sync::oneshot;
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryableError};
+use super::condition::RetryContext;
+
+/// Represents the errors of an action whose attempts are spawned onto an executor.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum IsolationError<E> {
+    /// The executor refused to spawn the attempt, e.g. because it is shutting down.
+    Rejected,
+    /// The task of the attempt was dropped before it completed, e.g. because it panicked.
+    Lost,
+    /// The attempt failed.
+    Failed(E)
+}
+
+impl<E: fmt::Display> fmt::Display for IsolationError<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            IsolationError::Rejected => write!(formatter, "executor refused to spawn the attempt"),
+            IsolationError::Lost => write!(formatter, "attempt was dropped before it completed"),
+            IsolationError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error> Error for IsolationError<E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            IsolationError::Rejected | IsolationError::Lost => None,
+            IsolationError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Lost attempts are retryable, rejected ones are not, as the executor is
+/// unlikely to accept them later; other errors only if they report to be.
+impl<E: RetryableError> RetryableError for IsolationError<E> {
+    fn is_retryable(&self) -> bool {
+        match *self {
+            IsolationError::Rejected => false,
+            IsolationError::Lost => true,
+            IsolationError::Failed(ref err) => err.is_retryable()
+        }
+    }
+}
+
+/// An action adapter which spawns every attempt onto an executor as a task of its own,
+/// and receives its result through a `oneshot` channel.
+///
+/// This isolates the retry sequence from attempts which block or monopolize their task,
+/// and allows attempts to be cancelled without their cooperation: once the future of an
+/// attempt is dropped, e.g. by a [`Timeout`](struct.Timeout.html), its task drops the
+/// attempt the next time it is polled.
+///
+/// This `struct` is created by `RetryFuture::spawn_isolated`, or can be constructed
+/// directly, e.g. to be bounded via `RetryFuture::spawn_with_timeout`.
+pub struct Isolated<A, X> {
+    action: A,
+    executor: X
+}
+
+impl<A, X> Isolated<A, X> {
+    /// Constructs an action spawning the attempts of the given action onto the executor.
+    pub fn new(executor: X, action: A) -> Isolated<A, X> {
+        Isolated{action, executor}
+    }
+}
+
+impl<A: Action, X: Executor<Attempt<A::Future>>> Isolated<A, X> {
+    fn isolate(&mut self, inner: A::Future) -> IsolatedFuture<A::Item, A::Error> {
+        let (sender, receiver) = oneshot::channel();
+        let spawned = self.executor.execute(Attempt{inner, sender: Some(sender)}).is_ok();
+        IsolatedFuture{receiver: Some(receiver).filter(|_| spawned)}
+    }
+}
+
+impl<A: Action, X: Executor<Attempt<A::Future>>> Action for Isolated<A, X> {
+    type Item = A::Item;
+    type Error = IsolationError<A::Error>;
+    type Future = IsolatedFuture<A::Item, A::Error>;
+
+    fn run(&mut self) -> Self::Future {
+        let inner = self.action.run();
+        self.isolate(inner)
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        let inner = match *previous {
+            IsolationError::Failed(ref err) => self.action.run_after(err),
+            _ => self.action.run()
+        };
+        self.isolate(inner)
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            IsolationError::Failed(ref err) => Some(err),
+            _ => None
+        });
+        let inner = self.action.run_with_context(previous, context);
+        self.isolate(inner)
+    }
+}
+
+/// The task of a single attempt, as spawned by an [`Isolated`](struct.Isolated.html) action.
+pub struct Attempt<F: Future> {
+    inner: F,
+    sender: Option<oneshot::Sender<Result<F::Item, F::Error>>>
+}
+
+impl<F: Future> Future for Attempt<F> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        let cancelled = match self.sender {
+            Some(ref mut sender) => sender.poll_cancel().map(|cancelled| cancelled.is_ready()).unwrap_or(true),
+            None => true
+        };
+        if cancelled {
+            return Ok(Async::Ready(()));
+        }
+        let res = match self.inner.poll() {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(item)) => Ok(item),
+            Err(err) => Err(err)
+        };
+        // The receiver may have been dropped in the meantime, discarding the result.
+        drop(self.sender.take().expect("polled Attempt after completion").send(res));
+        Ok(Async::Ready(()))
+    }
+}
+
+/// Future of a single attempt at an [`Isolated`](struct.Isolated.html) action,
+/// resolving with the result of its task.
+pub struct IsolatedFuture<T, E> {
+    receiver: Option<oneshot::Receiver<Result<T, E>>>
+}
+
+impl<T, E> Future for IsolatedFuture<T, E> {
+    type Item = T;
+    type Error = IsolationError<E>;
+
+    fn poll(&mut self) -> Poll<T, IsolationError<E>> {
+        let receiver = match self.receiver {
+            Some(ref mut receiver) => receiver,
+            None => return Err(IsolationError::Rejected)
+        };
+        match receiver.poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(Ok(item))) => Ok(Async::Ready(item)),
+            Ok(Async::Ready(Err(err))) => Err(IsolationError::Failed(err)),
+            Err(oneshot::Canceled) => Err(IsolationError::Lost)
+        }
+    }
+}
+
+#[test]
+fn cancels_attempts_once_their_futures_are_dropped() {
+    use futures::future::{empty, Empty, ExecuteError};
+    use std::cell::RefCell;
+
+    struct Deferred(RefCell<Vec<Attempt<Empty<(), ()>>>>);
+
+    impl Executor<Attempt<Empty<(), ()>>> for Deferred {
+        fn execute(&self, attempt: Attempt<Empty<(), ()>>) -> Result<(), ExecuteError<Attempt<Empty<(), ()>>>> {
+            self.0.borrow_mut().push(attempt);
+            Ok(())
+        }
+    }
+
+    let mut action = Isolated::new(Deferred(RefCell::new(Vec::new())), empty::<(), ()>);
+    drop(action.run());
+
+    let mut attempt = action.executor.0.borrow_mut().pop().unwrap();
+    let polled = futures::future::lazy(|| Ok::<_, ()>(attempt.poll())).wait().unwrap();
+    assert_eq!(polled, Ok(Async::Ready(())));
+}
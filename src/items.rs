@@ -0,0 +1,145 @@
+use futures::{Async, Future, IntoFuture, Poll, Stream, try_ready};
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryError, RetryFuture, Sleep};
+use super::strategy::{BackoffStrategy, IntoStrategy};
+
+/// An item whose processing failed, despite being retried, along with the reason.
+///
+/// Items can be dead-lettered, e.g. by forwarding them to a separate queue,
+/// or skipped via `filter_map(Result::ok)`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DeadLetter<T, E> {
+    /// The item which could not be processed.
+    pub item: T,
+    /// The error of processing it.
+    pub error: E
+}
+
+impl<T, E: fmt::Display> fmt::Display for DeadLetter<T, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "processing item failed: {}", self.error)
+    }
+}
+
+impl<T: fmt::Debug, E: Error> Error for DeadLetter<T, E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        Some(&self.error)
+    }
+}
+
+/// The action processing a single item of a [`RetryItems`](struct.RetryItems.html) stream.
+pub struct ItemAction<T, F> {
+    item: T,
+    handler: F
+}
+
+impl<T, F: FnMut(&T) -> R, R: IntoFuture> Action for ItemAction<T, F> {
+    type Item = R::Item;
+    type Error = R::Error;
+    type Future = R::Future;
+
+    fn run(&mut self) -> Self::Future {
+        (self.handler)(&self.item).into_future()
+    }
+}
+
+/// The outcome of processing a single item of a [`RetryItems`](struct.RetryItems.html) stream.
+type ItemResult<T, R, S> = Result<<R as IntoFuture>::Item, DeadLetter<T, RetryError<<R as IntoFuture>::Error, <<S as Sleep>::Future as Future>::Error>>>;
+
+/// Stream that processes each item of an inner stream via a handler, retrying the processing
+/// of every item independently, with a clone of the strategy each.
+///
+/// Items are processed one at a time, in order, and yielded as the result of processing them:
+/// items which could not be processed, despite being retried, are yielded as a
+/// [`DeadLetter`](struct.DeadLetter.html), and do not end the stream. Errors of the inner
+/// stream are passed through.
+///
+/// This `struct` is created by the [`retry_items`](trait.RetryStreamExt.html#method.retry_items)
+/// method on [`RetryStreamExt`](trait.RetryStreamExt.html).
+pub struct RetryItems<St, S, I, F, R>
+    where St: Stream, S: Sleep, I: BackoffStrategy<R::Error>, F: FnMut(&St::Item) -> R, R: IntoFuture {
+    stream: St,
+    sleep: S,
+    strategy: I,
+    handler: Option<F>,
+    current: Option<RetryFuture<S, I, ItemAction<St::Item, F>>>
+}
+
+impl<St, S, I, F, R> Stream for RetryItems<St, S, I, F, R>
+    where St: Stream, S: Sleep + Clone, I: BackoffStrategy<R::Error> + Clone, F: FnMut(&St::Item) -> R, R: IntoFuture {
+    type Item = ItemResult<St::Item, R, S>;
+    type Error = St::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let polled = match self.current {
+                Some(ref mut current) => current.poll(),
+                None => {
+                    let item = match try_ready!(self.stream.poll()) {
+                        Some(item) => item,
+                        None => return Ok(Async::Ready(None))
+                    };
+                    let handler = self.handler.take().expect("polled RetryItems after completion");
+                    let action = ItemAction{item, handler};
+                    self.current = Some(RetryFuture::new(self.sleep.clone(), self.strategy.clone(), action));
+                    continue;
+                }
+            };
+            let result = match polled {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(processed)) => Ok(processed),
+                Err(err) => Err(err)
+            };
+            let action = self.current.take().expect("polled RetryItems after completion").into_inner();
+            self.handler = Some(action.handler);
+            let item = action.item;
+            return Ok(Async::Ready(Some(result.map_err(|error| DeadLetter{item, error}))));
+        }
+    }
+}
+
+/// Extension methods for retrying streams.
+pub trait RetryStreamExt: Stream + Sized {
+    /// Processes each item via the handler, retrying the processing of every item
+    /// independently, see [`RetryItems`](struct.RetryItems.html).
+    ///
+    /// This suits consumers of work queues, where a single poisonous item should be
+    /// dead-lettered, rather than fail or stall the whole stream.
+    fn retry_items<S, T, I, F, R>(self, sleep: S, strategy: T, handler: F) -> RetryItems<Self, S, I, F, R>
+        where S: Sleep + Clone, T: IntoStrategy<Strategy=I>, I: BackoffStrategy<R::Error> + Clone, F: FnMut(&Self::Item) -> R, R: IntoFuture {
+        RetryItems{stream: self, sleep, strategy: strategy.into_strategy(), handler: Some(handler), current: None}
+    }
+}
+
+impl<St: Stream> RetryStreamExt for St {}
+
+#[test]
+fn retries_each_item_independently() {
+    use futures::stream::iter_ok;
+    use std::collections::HashMap;
+    use super::strategy::NoDelay;
+
+    let mut attempts = HashMap::new();
+    let results = iter_ok::<_, ()>(vec![1, 2, 3]).retry_items(tokio_timer::Timer::default(), NoDelay.take(1), |&item: &u64| {
+        let attempt = attempts.entry(item).or_insert(0);
+        *attempt += 1;
+        match item {
+            1 if *attempt < 2 => Err("flaky"),
+            2 => Err("poisonous"),
+            _ => Ok(item * 10)
+        }
+    }).collect().wait().unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0], Ok(10));
+    match results[1] {
+        Err(DeadLetter{item: 2, error: RetryError::RetriesExhausted{last_error: "poisonous", attempts: 2, ..}}) => {},
+        ref other => panic!("item was not dead-lettered: {:?}", other)
+    }
+    assert_eq!(results[2], Ok(30));
+}
@@ -23,6 +23,58 @@
 //! features = ["tokio_core"]
 //! ```
 //!
+//! With the `tokio_timer` feature, `RetryFuture::spawn_default` sleeps on a lazily created,
+//! process-wide [`DefaultTimer`](struct.DefaultTimer.html), so that no timer needs to be passed in.
+//!
+//! Applications sleeping in tens of thousands of retry sequences at once can instead share
+//! a single, coarse timer wheel via [`RetryScheduler`](struct.RetryScheduler.html).
+//!
+//! Jitter is drawn from the [`rand`](https://crates.io/crates/rand) crate via the default `rand` feature.
+//! Without it, a small built-in xorshift generator is used instead, which avoids the dependency
+//! on targets where `rand` does not build.
+//!
+//! The optional `backoff` feature allows strategies of the [`backoff`](https://crates.io/crates/backoff)
+//! crate to be used via [`strategy::from_backoff`](strategy/fn.from_backoff.html).
+//!
+//! The optional `hyper` feature classifies `hyper::Error` and `http::StatusCode`
+//! as [`RetryableError`](trait.RetryableError.html)s, so that HTTP clients can be
+//! retried via `RetryFuture::spawn_transient` or `RetryService::new_transient`.
+//!
+//! The optional `anyhow` feature allows conditions to inspect `anyhow::Error`s via
+//! [`condition::retry_if_downcast`](condition/fn.retry_if_downcast.html).
+//!
+//! The optional `serde` feature makes errors and statistics `Serialize`,
+//! so that they can be shipped to log pipelines as structured data.
+//!
+//! The optional `tracing` feature instruments every retry sequence with a
+//! [`tracing`](https://crates.io/crates/tracing) span, containing a child span per attempt in
+//! which the action runs, and events for failed attempts, retries and their delays. The errors
+//! themselves can be recorded via `RetryFuture::trace_errors`.
+//!
+//! The optional `otel` feature extends these spans for OpenTelemetry exporters such as
+//! `tracing-opentelemetry`: attempt spans carry the semantic-convention attributes
+//! `retry.count` and `retry.delay` (in milliseconds), and are linked to the span which was current
+//! when the retry sequence started, so that retried calls are rendered as such.
+//!
+//! For applications not using `tracing`, the optional `log` feature emits
+//! [`log`](https://crates.io/crates/log) records instead: a warning for each retry, and an error
+//! once retries are exhausted. Their target can be configured via `RetryFuture::log_target`.
+//!
+//! The optional `metrics` feature reports to the [`metrics`](https://crates.io/crates/metrics)
+//! facade: the counters `retry_attempts_total`, `retry_retries_total` and `retry_exhausted_total`,
+//! as well as the histograms `retry_sleep_duration_seconds` and `retry_attempt_duration_seconds`.
+//! All of them are labeled with the `policy` given via `RetryFuture::named`.
+//!
+//! Names given via `RetryFuture::named` also flow into the `tracing` span and `log` records,
+//! so that telemetry can be aggregated per logical operation.
+//!
+//! The optional `tokio_io` feature provides [`ReconnectingIo`](struct.ReconnectingIo.html),
+//! an `AsyncRead + AsyncWrite` adapter which transparently reconnects persistent connections.
+//!
+//! `RetryFuture` also implements `std::future::Future`, so that it can be `.await`ed directly,
+//! and `RetryFuture::spawn_async` retries closures returning `std` futures, such as `async` blocks.
+//! The [`compat`](compat/index.html) module bridges the remaining futures 0.1 and `std` futures.
+//!
 //! # Examples
 //!
 //! ```rust
@@ -35,7 +87,7 @@
 //! use futures::future::Future;
 //! use tokio_timer::Timer;
 //! use tokio_retry::RetryFuture;
-//! use tokio_retry::strategy::{ExponentialBackoff, jitter};
+//! use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
 //!
 //! fn action() -> Result<u64, ()> {
 //!     // do some real-world stuff here...
@@ -44,8 +96,8 @@
 //!
 //! pub fn main() {
 //!     let retry_strategy = ExponentialBackoff::from_millis(10)
-//!         .take(3)
-//!         .map(jitter);
+//!         .jittered()
+//!         .take(3);
 //!     let retry_future = RetryFuture::spawn(Timer::default(), retry_strategy, action);
 //!     let retry_result = retry_future.wait();
 //!
@@ -53,24 +105,108 @@
 //! }
 //! ```
 
-extern crate either;
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "backoff")]
+extern crate backoff;
 extern crate futures;
+#[cfg(feature = "hyper")]
+extern crate http;
+#[cfg(feature = "hyper")]
+extern crate hyper;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "metrics")]
+extern crate metrics;
+#[cfg(feature = "rand")]
 extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
 #[cfg(feature = "tokio_core")]
 extern crate tokio_core;
+#[cfg(feature = "tokio_io")]
+extern crate tokio_io;
 #[cfg(feature = "tokio_timer")]
 extern crate tokio_timer;
 #[cfg(feature = "tokio_service")]
 extern crate tokio_service;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(all(test, feature = "otel"))]
+extern crate tracing_core;
 
 mod action;
+mod batch;
+mod cancel;
+pub mod compat;
+pub mod condition;
+mod deadline;
+mod expiry;
+mod fallback;
 mod future;
+mod handle;
+mod hedge;
+mod hook;
+#[cfg(feature = "hyper")]
+mod http_errors;
+#[cfg(feature = "tokio_io")]
+mod io;
+mod isolate;
+mod items;
+mod limiter;
 #[cfg(feature = "tokio_service")]
 mod middleware;
-/// Assorted retry strategies including fixed interval and exponential back-off.
+mod policy;
+mod race;
+mod reclaim;
+mod reconnect;
+mod scheduler;
+mod sink;
+mod stats;
+mod std_future;
+mod stream;
+mod supervisor;
+mod telemetry;
 pub mod strategy;
+mod ticks;
+mod timeout;
+mod unwind;
+mod until;
+mod watch;
 
-pub use action::Action;
+pub use action::{Action, ActionWithContext, FailoverAction, WithPreviousError};
+pub use batch::{retry_all, BatchOutcome, BatchStats, RetryAll};
+pub use cancel::{Cancellable, CancelError, UntilShutdown};
+pub use condition::RetryableError;
+pub use deadline::{Deadline, DeadlineError};
+pub use fallback::{Fallback, FallbackError, Recover};
 pub use future::{Sleep, RetryError, RetryFuture};
+#[cfg(feature = "tokio_timer")]
+pub use future::DefaultTimer;
+pub use handle::{AbortSignal, RetryHandle};
+pub use hedge::{Hedge, HedgeError, HedgeFuture};
+pub use hook::{RetryHook, NoHook, OnRetry, RetryEvent, EventSender};
+#[cfg(feature = "tracing")]
+pub use hook::TraceErrors;
+#[cfg(feature = "tokio_io")]
+pub use io::ReconnectingIo;
+pub use isolate::{Attempt, Isolated, IsolatedFuture, IsolationError};
+pub use items::{DeadLetter, ItemAction, RetryItems, RetryStreamExt};
+pub use limiter::KeyedLimiter;
+pub use policy::{RetryPolicy, Transient, Permanent};
+pub use race::{RaceError, RetryRace};
+pub use reclaim::ReclaimAction;
+pub use reconnect::ReconnectLoop;
+pub use scheduler::{RetryScheduler, ScheduledSleep};
+pub use sink::RetrySink;
+pub use stats::{Outcome, RetryStats, WithStats};
+pub use stream::{Checkpointed, RetryStream, StreamFactory};
+pub use std_future::{AsyncAction, AsyncAttempt};
+pub use supervisor::{Exit, Supervised, SupervisedFuture, Supervisor};
+pub use ticks::Ticks;
+pub use timeout::{Timeout, TimeoutError, TimeoutFuture};
+pub use unwind::{CatchUnwind, CatchUnwindFuture, UnwindError};
+pub use until::{Until, UntilError, UntilFuture};
+pub use watch::{RetryOutcome, RetryStatus, RetryWatch};
 #[cfg(feature = "tokio_service")]
 pub use middleware::{RetryService, ServiceRetryFuture, ServiceAction};
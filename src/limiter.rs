@@ -0,0 +1,208 @@
+use futures::task::{self, Task};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// Limits how many retry sequences per key, e.g. per downstream host, may be retrying at once,
+/// so that a single unavailable shard cannot consume a whole connection pool with retries.
+///
+/// A retry sequence counts against the limit of its key from its first retry until it completes
+/// or is dropped; initial attempts are never limited. Once the limit is reached, further retry
+/// sequences wait for a retrying one to complete before retrying, or, with `fail_fast`, give up
+/// with `RetryError::Rejected`. Clones share the same limits, and keys are forgotten once no
+/// retry sequence is limited by them any more.
+///
+/// Retry sequences are limited via the `limit_retries` method on `RetryFuture`.
+#[derive(Clone)]
+pub struct KeyedLimiter<K> {
+    limit: usize,
+    fail_fast: bool,
+    slots: Arc<Mutex<HashMap<K, Arc<Slot>>>>
+}
+
+impl<K> fmt::Debug for KeyedLimiter<K> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("KeyedLimiter")
+            .field("limit", &self.limit)
+            .field("fail_fast", &self.fail_fast)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Hash + Eq> KeyedLimiter<K> {
+    /// Constructs a limiter allowing up to `limit` retry sequences per key to retry at once.
+    pub fn new(limit: usize) -> KeyedLimiter<K> {
+        KeyedLimiter{limit, fail_fast: false, slots: Arc::new(Mutex::new(HashMap::new()))}
+    }
+
+    /// Makes retry sequences give up instead of waiting, once the limit of their key is reached.
+    pub fn fail_fast(mut self) -> KeyedLimiter<K> {
+        self.fail_fast = true;
+        self
+    }
+
+    /// Returns the number of retry sequences currently retrying for the given key.
+    pub fn retrying(&self, key: &K) -> usize {
+        match self.slots.lock().unwrap().get(key) {
+            Some(slot) => slot.state.lock().unwrap().active,
+            None => 0
+        }
+    }
+
+    pub(crate) fn limit(&self, key: K) -> RetryLimit where K: Clone + Send + 'static {
+        let (slot, id) = {
+            let mut slots = self.slots.lock().unwrap();
+            let slot = slots.entry(key.clone())
+                .or_insert_with(|| Arc::new(Slot{state: Mutex::new(SlotState{active: 0, users: 0, next_id: 0, waiters: HashMap::new()})}))
+                .clone();
+            let id = {
+                let mut state = slot.state.lock().unwrap();
+                state.users += 1;
+                state.next_id += 1;
+                state.next_id
+            };
+            (slot, id)
+        };
+        let slots = self.slots.clone();
+        // Slots are only ever evicted under the lock of the map, so that a new limit of the
+        // same key either shares the slot, or creates a fresh one once it is gone.
+        let detach = Box::new(move |slot: &Slot, id: usize| {
+            let mut slots = slots.lock().unwrap();
+            let mut state = slot.state.lock().unwrap();
+            state.waiters.remove(&id);
+            state.users -= 1;
+            if state.users == 0 {
+                slots.remove(&key);
+            }
+        });
+        RetryLimit{slot, id, limit: self.limit, fail_fast: self.fail_fast, held: false, detach}
+    }
+}
+
+struct Slot {
+    state: Mutex<SlotState>
+}
+
+struct SlotState {
+    active: usize,
+    users: usize,
+    next_id: usize,
+    waiters: HashMap<usize, Task>
+}
+
+type Detach = Box<dyn Fn(&Slot, usize) + Send>;
+
+/// The limit of a single retry sequence, holding a permit of its key while retrying.
+pub(crate) struct RetryLimit {
+    slot: Arc<Slot>,
+    id: usize,
+    limit: usize,
+    fail_fast: bool,
+    held: bool,
+    detach: Detach
+}
+
+impl RetryLimit {
+    /// Returns whether a retry may be scheduled: always when waiting for permits,
+    /// otherwise only if a permit is held or could be acquired.
+    pub(crate) fn admit(&mut self) -> bool {
+        !self.fail_fast || self.acquire(false)
+    }
+
+    /// Returns whether a permit is held, or else arranges for the current task
+    /// to be notified once one may be available.
+    pub(crate) fn poll_acquire(&mut self) -> bool {
+        self.acquire(true)
+    }
+
+    fn acquire(&mut self, register: bool) -> bool {
+        if self.held {
+            return true;
+        }
+        let mut state = self.slot.state.lock().unwrap();
+        if state.active < self.limit {
+            state.active += 1;
+            state.waiters.remove(&self.id);
+            self.held = true;
+        } else if register {
+            // Registering again replaces the task registered by a previous poll.
+            state.waiters.insert(self.id, task::current());
+        }
+        self.held
+    }
+
+    /// Releases the permit, if held, waking the retry sequences waiting for one.
+    pub(crate) fn release(&mut self) {
+        if !self.held {
+            return;
+        }
+        self.held = false;
+        let waiters = {
+            let mut state = self.slot.state.lock().unwrap();
+            state.active -= 1;
+            mem::take(&mut state.waiters)
+        };
+        for waiter in waiters.into_values() {
+            waiter.notify();
+        }
+    }
+}
+
+impl Drop for RetryLimit {
+    fn drop(&mut self) {
+        self.release();
+        (self.detach)(&self.slot, self.id);
+    }
+}
+
+#[test]
+fn limits_concurrent_retries_per_key() {
+    use futures::future::lazy;
+    use futures::Future;
+
+    let limiter = KeyedLimiter::new(1);
+    let mut first = limiter.limit("a");
+    let mut second = limiter.limit("a");
+    let mut other = limiter.limit("b");
+
+    lazy(|| {
+        assert!(first.poll_acquire());
+        assert!(!second.poll_acquire());
+        assert!(other.poll_acquire());
+        assert_eq!(limiter.retrying(&"a"), 1);
+
+        first.release();
+        assert!(second.poll_acquire());
+        Ok::<_, ()>(())
+    }).wait().unwrap();
+
+    drop(second);
+    assert_eq!(limiter.retrying(&"a"), 0);
+}
+
+#[test]
+fn registers_waiters_once_and_forgets_unused_keys() {
+    use futures::future::lazy;
+    use futures::Future;
+
+    let limiter = KeyedLimiter::new(1);
+    let mut first = limiter.limit("a");
+    let mut second = limiter.limit("a");
+
+    lazy(|| {
+        assert!(first.poll_acquire());
+        assert!(!second.poll_acquire());
+        assert!(!second.poll_acquire());
+        Ok::<_, ()>(())
+    }).wait().unwrap();
+    assert_eq!(second.slot.state.lock().unwrap().waiters.len(), 1);
+
+    drop(second);
+    assert!(first.slot.state.lock().unwrap().waiters.is_empty());
+    assert_eq!(limiter.slots.lock().unwrap().len(), 1);
+
+    drop(first);
+    assert!(limiter.slots.lock().unwrap().is_empty());
+}
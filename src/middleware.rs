@@ -1,10 +1,11 @@
-use std::iter::{Iterator, IntoIterator};
-use std::time::Duration;
+use std::fmt;
 use std::sync::Arc;
 use futures::Future;
 use tokio_service::Service;
 
-use super::{Sleep, RetryFuture, RetryError, Action};
+use super::{Sleep, RetryFuture, RetryError, RetryableError, Action};
+use super::condition::{Always, Condition, Retryable};
+use super::strategy::{BackoffStrategy, IntoStrategy};
 
 pub struct ServiceAction<X: Service> {
     inner: Arc<X>,
@@ -21,36 +22,128 @@ impl<X: Service> Action for ServiceAction<X> where X::Request: Clone {
     }
 }
 
-pub type ServiceRetryFuture<S, I, X> = RetryFuture<S, I, ServiceAction<X>>;
+pub type ServiceRetryFuture<S, I, X, C = Always> = RetryFuture<S, I, ServiceAction<X>, C>;
 
-pub struct RetryService<S, I, X> {
+pub struct RetryService<S, I, X, C = Always> {
     inner: Arc<X>,
     sleep: S,
-    strategy: I
+    strategy: I,
+    condition: C
 }
 
-impl<S: Sleep, I: Iterator<Item=Duration>, X> RetryService<S, I, X> {
-    pub fn new<T: IntoIterator<IntoIter=I, Item=Duration>>(sleep: S, strategy: T, inner: X) -> RetryService<S, I, X> {
+impl<S, I: fmt::Debug, X, C: fmt::Debug> fmt::Debug for RetryService<S, I, X, C> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("RetryService")
+            .field("strategy", &self.strategy)
+            .field("condition", &self.condition)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Sleep, I, X> RetryService<S, I, X> {
+    pub fn new<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, inner: X) -> RetryService<S, I, X> {
+        RetryService::new_if(sleep, strategy, inner, Always)
+    }
+}
+
+impl<S: Sleep, I, X: Service> RetryService<S, I, X, Retryable> where X::Error: RetryableError {
+    /// Like `new`, but only retries errors which report to be
+    /// [retryable](trait.RetryableError.html).
+    pub fn new_transient<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, inner: X) -> RetryService<S, I, X, Retryable> {
+        RetryService::new_if(sleep, strategy, inner, Retryable)
+    }
+}
+
+impl<S: Sleep, I, X, C> RetryService<S, I, X, C> {
+    /// Like `new`, but only retries errors for which the condition holds.
+    pub fn new_if<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, inner: X, condition: C) -> RetryService<S, I, X, C> {
         RetryService{
             inner: Arc::new(inner),
-            sleep: sleep,
-            strategy: strategy.into_iter()
+            sleep,
+            strategy: strategy.into_strategy(),
+            condition
         }
     }
 }
 
-impl<S: Clone + Sleep, I: Clone + Iterator<Item=Duration>, X: Service> Service for RetryService<S, I, X> where X::Request: Clone {
+impl<S: Clone + Sleep, I: Clone + BackoffStrategy<X::Error>, X: Service, C: Clone + Condition<X::Error>> Service for RetryService<S, I, X, C> where X::Request: Clone {
     type Request = X::Request;
     type Response = X::Response;
     type Error = RetryError<X::Error, <S::Future as Future>::Error>;
-    type Future = ServiceRetryFuture<S, I, X>;
+    type Future = ServiceRetryFuture<S, I, X, C>;
 
     fn call(&self, request: Self::Request) -> Self::Future {
         let action = ServiceAction{
             inner: self.inner.clone(),
-            request: request
+            request
         };
 
-        RetryFuture::spawn(self.sleep.clone(), self.strategy.clone(), action)
+        RetryFuture::with_condition(self.sleep.clone(), self.strategy.clone(), action, self.condition.clone())
+    }
+}
+
+#[test]
+fn retries_with_a_stored_jittered_strategy() {
+    use std::cell::Cell;
+    use std::default::Default;
+    use std::time::Duration;
+    use futures::future::{ok, err, FutureResult};
+    use tokio_timer::Timer;
+    use super::strategy::{FixedInterval, Jittered, StrategyExt};
+
+    struct Config {
+        strategy: Jittered<FixedInterval>
+    }
+
+    struct Flaky {
+        calls: Cell<usize>
     }
+
+    impl Service for Flaky {
+        type Request = ();
+        type Response = usize;
+        type Error = ();
+        type Future = FutureResult<usize, ()>;
+
+        fn call(&self, _: ()) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            if self.calls.get() < 3 { err(()) } else { ok(self.calls.get()) }
+        }
+    }
+
+    let config = Config{strategy: FixedInterval::new(Duration::from_millis(10)).jittered()};
+    let service = RetryService::new(Timer::default(), config.strategy.clone().take(5), Flaky{calls: Cell::new(0)});
+
+    assert_eq!(service.call(()).wait(), Ok(3));
+}
+
+#[test]
+fn retries_only_transient_errors() {
+    use std::cell::Cell;
+    use std::default::Default;
+    use std::io;
+    use futures::future::{err, FutureResult};
+    use tokio_timer::Timer;
+    use super::strategy::NoDelay;
+
+    struct Denied {
+        calls: Cell<usize>
+    }
+
+    impl Service for Denied {
+        type Request = ();
+        type Response = ();
+        type Error = io::Error;
+        type Future = FutureResult<(), io::Error>;
+
+        fn call(&self, _: ()) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+        }
+    }
+
+    let service = RetryService::new_transient(Timer::default(), NoDelay.take(5), Denied{calls: Cell::new(0)});
+
+    assert!(service.call(()).wait().is_err());
+    assert_eq!(service.inner.calls.get(), 1);
 }
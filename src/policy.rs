@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::RetryableError;
+use super::strategy::BackoffHint;
+
+/// An error which lets the action itself decide whether and when to retry.
+///
+/// `RetryPolicy` is both a [`RetryableError`](trait.RetryableError.html) and a
+/// [`BackoffHint`](strategy/trait.BackoffHint.html), so actions failing with it
+/// can be retried via `RetryFuture::spawn_transient` with a
+/// [`hinted`](strategy/fn.hinted.html) strategy:
+///
+/// ```rust
+/// extern crate futures;
+/// extern crate tokio_timer;
+/// extern crate tokio_retry;
+///
+/// use std::time::Duration;
+/// use std::default::Default;
+/// use futures::future::Future;
+/// use tokio_timer::Timer;
+/// use tokio_retry::{RetryFuture, RetryError, RetryPolicy};
+/// use tokio_retry::strategy::{hinted, FixedInterval};
+///
+/// fn action() -> Result<u64, RetryPolicy<&'static str>> {
+///     Err(RetryPolicy::Stop("permanently broken"))
+/// }
+///
+/// pub fn main() {
+///     let strategy = hinted(FixedInterval::new(Duration::from_millis(10)).take(3));
+///     let retry_result = RetryFuture::spawn_transient(Timer::default(), strategy, action).wait();
+///
+///     assert_eq!(retry_result, Err(RetryError::OperationError(RetryPolicy::Stop("permanently broken"))));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RetryPolicy<E> {
+    /// Retry according to the strategy.
+    Retry(E),
+    /// Do not retry.
+    Stop(E),
+    /// Retry after the given delay, instead of the one of the strategy.
+    RetryAfter(E, Duration)
+}
+
+impl<E> RetryPolicy<E> {
+    /// Unwraps the policy, returning the underlying error.
+    pub fn into_inner(self) -> E {
+        match self {
+            RetryPolicy::Retry(err) | RetryPolicy::Stop(err) | RetryPolicy::RetryAfter(err, _) => err
+        }
+    }
+
+    /// Returns a reference to the underlying error.
+    pub fn get_ref(&self) -> &E {
+        match *self {
+            RetryPolicy::Retry(ref err) | RetryPolicy::Stop(ref err) | RetryPolicy::RetryAfter(ref err, _) => err
+        }
+    }
+}
+
+impl<E> RetryableError for RetryPolicy<E> {
+    fn is_retryable(&self) -> bool {
+        !matches!(*self, RetryPolicy::Stop(_))
+    }
+}
+
+impl<E> BackoffHint for RetryPolicy<E> {
+    fn retry_after(&self) -> Option<Duration> {
+        match *self {
+            RetryPolicy::RetryAfter(_, duration) => Some(duration),
+            _ => None
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RetryPolicy<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.get_ref().fmt(formatter)
+    }
+}
+
+impl<E: Error + 'static> Error for RetryPolicy<E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        Some(self.get_ref())
+    }
+}
+
+/// Wraps an error which should be retried.
+///
+/// Converts into `RetryPolicy::Retry`, so that actions failing with a
+/// [`RetryPolicy`](enum.RetryPolicy.html) can use `map_err(Transient)?`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transient<E>(pub E);
+
+/// Wraps an error which should not be retried.
+///
+/// Converts into `RetryPolicy::Stop`, so that actions failing with a
+/// [`RetryPolicy`](enum.RetryPolicy.html) can use `map_err(Permanent)?`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Permanent<E>(pub E);
+
+impl<E> From<Transient<E>> for RetryPolicy<E> {
+    fn from(err: Transient<E>) -> RetryPolicy<E> {
+        RetryPolicy::Retry(err.0)
+    }
+}
+
+impl<E> From<Permanent<E>> for RetryPolicy<E> {
+    fn from(err: Permanent<E>) -> RetryPolicy<E> {
+        RetryPolicy::Stop(err.0)
+    }
+}
+
+impl<E> RetryableError for Transient<E> {
+    fn is_retryable(&self) -> bool {
+        true
+    }
+}
+
+impl<E> RetryableError for Permanent<E> {
+    fn is_retryable(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn follows_the_policy_of_the_action() {
+    use std::default::Default;
+    use futures::Future;
+    use super::{RetryFuture, RetryError};
+    use super::strategy::{hinted, FixedInterval};
+    let mut num_calls = 0;
+    let strategy = hinted(FixedInterval::new(Duration::from_millis(1)).take(10));
+    let res = RetryFuture::spawn_transient(tokio_timer::Timer::default(), strategy, || {
+        num_calls += 1;
+        Err::<(), RetryPolicy<u64>>(match num_calls {
+            1 | 2 => RetryPolicy::RetryAfter(num_calls, Duration::from_millis(2)),
+            3 => RetryPolicy::Retry(num_calls),
+            _ => RetryPolicy::Stop(num_calls)
+        })
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(RetryPolicy::Stop(4))));
+}
+
+#[test]
+fn stops_on_permanent_errors() {
+    use std::default::Default;
+    use futures::Future;
+    use super::{RetryFuture, RetryError};
+    use super::strategy::NoDelay;
+
+    fn fetch(calls: u64) -> Result<(), RetryPolicy<u64>> {
+        if calls < 3 {
+            Err(Transient(calls))?
+        }
+        Err(Permanent(calls))?
+    }
+
+    let mut num_calls = 0;
+    let res = RetryFuture::spawn_transient(tokio_timer::Timer::default(), NoDelay.take(10), || {
+        num_calls += 1;
+        fetch(num_calls)
+    }).wait();
+
+    assert_eq!(res, Err(RetryError::OperationError(RetryPolicy::Stop(3))));
+}
@@ -0,0 +1,205 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::time::{Duration, Instant};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, Sleep};
+use super::condition::RetryContext;
+use super::expiry::Expiry;
+use super::strategy::IntoStrategy;
+
+/// Represents the errors of a race.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RaceError<E, TE> {
+    /// All attempts failed, with their errors in the order they failed.
+    AllFailed(Vec<E>),
+    /// Sleeping until the next attempt was due failed, e.g. because the delay exceeds the
+    /// `max_timeout` of the timer, so that the race was aborted rather than left unstaggered.
+    TimerError(TE)
+}
+
+impl<E: fmt::Display, TE: fmt::Display> fmt::Display for RaceError<E, TE> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            RaceError::AllFailed(ref errors) => {
+                write!(formatter, "all {} attempts failed", errors.len())?;
+                if let Some(err) = errors.last() {
+                    write!(formatter, ", the last with: {}", err)?;
+                }
+                Ok(())
+            },
+            RaceError::TimerError(ref err) => write!(formatter, "sleeping until the next attempt failed: {}", err)
+        }
+    }
+}
+
+impl<E: Error, TE: Error> Error for RaceError<E, TE> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            RaceError::AllFailed(ref errors) => errors.last().map(|err| err as &dyn Error),
+            RaceError::TimerError(ref err) => Some(err)
+        }
+    }
+}
+
+/// Future that races up to a number of concurrent attempts of an action, resolving with
+/// the first success, e.g. to query several mirrors when latency matters more than load.
+///
+/// The attempts are staggered by the delays of the strategy: the next attempt is started
+/// once the delay after the previous one has elapsed, or as soon as all running attempts
+/// have failed. Once an attempt succeeds, the remaining ones are dropped, as are the running
+/// ones should sleeping fail, with `RaceError::TimerError`. Every attempt is
+/// passed its [`RetryContext`](condition/struct.RetryContext.html), see `Action::run_with_context`.
+pub struct RetryRace<S: Sleep, I, A: Action> {
+    sleep: S,
+    strategy: I,
+    action: A,
+    remaining: usize,
+    launched: usize,
+    started: Option<Instant>,
+    running: Vec<A::Future>,
+    timer: Expiry<S::Future>,
+    errors: Vec<A::Error>
+}
+
+impl<S: Sleep, I: Iterator<Item=Duration>, A: Action> RetryRace<S, I, A> {
+    /// Races up to `attempts` attempts of the given action, staggered by the given strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attempts` is zero.
+    pub fn spawn<T: IntoStrategy<Strategy=I>>(sleep: S, attempts: usize, strategy: T, action: A) -> RetryRace<S, I, A> {
+        assert!(attempts > 0, "RetryRace requires at least one attempt");
+        RetryRace{
+            sleep,
+            strategy: strategy.into_strategy(),
+            action,
+            remaining: attempts,
+            launched: 0,
+            started: None,
+            running: Vec::new(),
+            timer: Expiry::never(),
+            errors: Vec::new()
+        }
+    }
+
+    /// Returns the number of attempts which are currently running.
+    pub fn running(&self) -> usize {
+        self.running.len()
+    }
+
+    fn launch(&mut self) {
+        self.remaining -= 1;
+        self.launched += 1;
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let context = RetryContext::new(self.launched, started.elapsed());
+        self.running.push(self.action.run_with_context(None, &context));
+        self.timer = match self.remaining {
+            0 => Expiry::never(),
+            _ => match self.strategy.next() {
+                Some(delay) => Expiry::new(self.sleep.sleep(delay)),
+                None => {
+                    self.remaining = 0;
+                    Expiry::never()
+                }
+            }
+        };
+    }
+}
+
+impl<S: Sleep, I: Iterator<Item=Duration>, A: Action> Future for RetryRace<S, I, A> {
+    type Item = A::Item;
+    type Error = RaceError<A::Error, <S::Future as Future>::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let mut index = 0;
+            while index < self.running.len() {
+                match self.running[index].poll() {
+                    Ok(Async::Ready(item)) => {
+                        self.running.clear();
+                        return Ok(Async::Ready(item));
+                    },
+                    Ok(Async::NotReady) => index += 1,
+                    Err(err) => {
+                        drop(self.running.swap_remove(index));
+                        self.errors.push(err);
+                    }
+                }
+            }
+
+            let staggered = match self.timer.poll_expired() {
+                Ok(staggered) => staggered,
+                Err(err) => {
+                    self.running.clear();
+                    return Err(RaceError::TimerError(err));
+                }
+            };
+
+            if self.remaining > 0 && (staggered || self.running.is_empty()) {
+                self.launch();
+                continue;
+            }
+            if self.running.is_empty() {
+                return Err(RaceError::AllFailed(mem::take(&mut self.errors)));
+            }
+            return Ok(Async::NotReady);
+        }
+    }
+}
+
+#[test]
+fn resolves_with_the_first_success() {
+    use futures::future::{empty, ok};
+    use tokio_timer::Timer;
+    use super::strategy::FixedInterval;
+
+    let mut runs = 0;
+    let action = move || -> Box<dyn Future<Item=usize, Error=()>> {
+        runs += 1;
+        match runs {
+            1 => Box::new(empty()),
+            run => Box::new(ok(run))
+        }
+    };
+    let race = RetryRace::spawn(Timer::default(), 3, FixedInterval::new(Duration::from_millis(10)), action);
+
+    assert_eq!(race.wait(), Ok(2));
+}
+
+#[test]
+fn aggregates_the_errors_of_all_attempts() {
+    use tokio_timer::Timer;
+    use super::strategy::FixedInterval;
+
+    let mut runs = 0;
+    let action = move || {
+        runs += 1;
+        Err::<(), usize>(runs)
+    };
+    let race = RetryRace::spawn(Timer::default(), 3, FixedInterval::new(Duration::from_secs(10)), action);
+
+    assert_eq!(race.wait(), Err(RaceError::AllFailed(vec![1, 2, 3])));
+}
+
+#[test]
+fn aborts_once_sleeping_until_the_next_attempt_failed() {
+    use futures::future::{empty, err, Empty, FutureResult};
+    use super::strategy::NoDelay;
+
+    struct BrokenSleep;
+
+    impl Sleep for BrokenSleep {
+        type Future = FutureResult<(), &'static str>;
+        fn sleep(&mut self, _: Duration) -> Self::Future {
+            err("too long")
+        }
+    }
+
+    let race = RetryRace::spawn(BrokenSleep, 3, NoDelay, empty::<(), ()> as fn() -> Empty<(), ()>);
+    assert_eq!(race.wait(), Err(RaceError::TimerError("too long")));
+}
@@ -0,0 +1,44 @@
+use futures::{Async, Future, Poll};
+
+use super::{Action, RetryError, RetryFuture, Sleep};
+use super::condition::Condition;
+use super::hook::RetryHook;
+use super::strategy::BackoffStrategy;
+
+type ReclaimError<S, A> = (RetryError<<A as Action>::Error, <<S as Sleep>::Future as Future>::Error>, A);
+
+/// Future that resolves to the outcome of a `RetryFuture` along with its action,
+/// including any state the action accumulated.
+///
+/// This `struct` is created by the `reclaim_action` method on `RetryFuture`.
+pub struct ReclaimAction<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    inner: Option<RetryFuture<S, I, A, C, H>>
+}
+
+impl<S, I, A, C, H> ReclaimAction<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    pub(crate) fn new(inner: RetryFuture<S, I, A, C, H>) -> ReclaimAction<S, I, A, C, H> {
+        ReclaimAction{inner: Some(inner)}
+    }
+}
+
+impl<S, I, A, C, H> Future for ReclaimAction<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    type Item = (A::Item, A);
+    type Error = ReclaimError<S, A>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let result = match self.inner {
+            Some(ref mut inner) => match inner.poll() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(item)) => Ok(item),
+                Err(err) => Err(err)
+            },
+            None => panic!("polled ReclaimAction after completion")
+        };
+
+        let action = self.inner.take().expect("polled ReclaimAction after completion").into_inner();
+        match result {
+            Ok(item) => Ok(Async::Ready((item, action))),
+            Err(err) => Err((err, action))
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use futures::{task, Async, Future, IntoFuture, Poll};
+use std::cmp;
+use std::mem;
+use std::time::{Duration, Instant};
+
+use super::{Action, RetryError, Sleep};
+use super::future::DEFAULT_YIELD_AFTER;
+use super::strategy::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// The default time a connection needs to stay up to reset the back-off.
+const DEFAULT_RESET_AFTER: Duration = Duration::from_secs(30);
+
+enum ReconnectState<S: Sleep, C: Action, U: IntoFuture> {
+    Pending,
+    Connecting(C::Future),
+    Connected(U::Future, Instant),
+    Sleeping(S::Future)
+}
+
+/// Future that keeps a connection up: it connects with back-off, hands each established
+/// connection to a session closure, and reconnects whenever the session fails.
+///
+/// Failures to connect and failed sessions are both delayed by the strategy, so that flapping
+/// connections back off as well. Once a connection has stayed up for a while, 30 seconds by
+/// default, the strategy is reset, so that the next reconnect starts over with short delays.
+///
+/// The loop completes once a session completes successfully, or fails with
+/// `RetryError::RetriesExhausted` should the strategy give up.
+pub struct ReconnectLoop<S: Sleep, I, C: Action, F, U: IntoFuture> {
+    sleep: S,
+    strategy: I,
+    connect: C,
+    session: F,
+    reset_after: Duration,
+    yield_after: usize,
+    attempt: usize,
+    reconnects: usize,
+    started: Instant,
+    state: ReconnectState<S, C, U>
+}
+
+impl<S, I, C, F, U> ReconnectLoop<S, I, C, F, U>
+    where S: Sleep, I: BackoffStrategy<C::Error> + ResettableStrategy, C: Action, F: FnMut(C::Item) -> U, U: IntoFuture<Error=C::Error> {
+    /// Constructs a reconnect loop, connecting via the given action and running
+    /// the closure with each established connection.
+    pub fn new<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, connect: C, session: F) -> ReconnectLoop<S, I, C, F, U> {
+        ReconnectLoop{
+            sleep,
+            strategy: strategy.into_strategy(),
+            connect,
+            session,
+            reset_after: DEFAULT_RESET_AFTER,
+            yield_after: DEFAULT_YIELD_AFTER,
+            attempt: 0,
+            reconnects: 0,
+            started: Instant::now(),
+            state: ReconnectState::Pending
+        }
+    }
+
+    /// Sets how long a connection needs to stay up for the strategy to be reset.
+    pub fn reset_after(mut self, duration: Duration) -> ReconnectLoop<S, I, C, F, U> {
+        self.reset_after = duration;
+        self
+    }
+
+    /// Yields to the executor after the given number of consecutive state transitions
+    /// within a single poll, which defaults to 64, see `RetryFuture::yield_after`.
+    pub fn yield_after(mut self, transitions: usize) -> ReconnectLoop<S, I, C, F, U> {
+        self.yield_after = cmp::max(transitions, 1);
+        self
+    }
+
+    /// Returns whether a connection is currently established.
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, ReconnectState::Connected(..))
+    }
+
+    /// Returns the number of times the connection was re-established after a failed session.
+    pub fn reconnects(&self) -> usize {
+        self.reconnects
+    }
+
+    fn retry(&mut self, err: C::Error) -> Result<(), RetryError<C::Error, <S::Future as Future>::Error>> {
+        if self.attempt == 0 {
+            self.started = Instant::now();
+        }
+        self.attempt += 1;
+        match self.strategy.delay(self.attempt, &err) {
+            Some(delay) => {
+                self.state = ReconnectState::Sleeping(self.sleep.sleep(delay));
+                Ok(())
+            },
+            None => Err(RetryError::RetriesExhausted{
+                last_error: err,
+                attempts: self.attempt,
+                total_elapsed: self.started.elapsed(),
+                recent_errors: Vec::new(),
+                attempts_exhausted: self.strategy.attempts_exhausted()
+            })
+        }
+    }
+}
+
+impl<S, I, C, F, U> Future for ReconnectLoop<S, I, C, F, U>
+    where S: Sleep, I: BackoffStrategy<C::Error> + ResettableStrategy, C: Action, F: FnMut(C::Item) -> U, U: IntoFuture<Error=C::Error> {
+    type Item = U::Item;
+    type Error = RetryError<C::Error, <S::Future as Future>::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        for _ in 0..self.yield_after {
+            self.state = match mem::replace(&mut self.state, ReconnectState::Pending) {
+                ReconnectState::Pending => ReconnectState::Connecting(self.connect.run()),
+                ReconnectState::Connecting(mut future) => match future.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = ReconnectState::Connecting(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(connection)) => {
+                        self.strategy.succeeded();
+                        ReconnectState::Connected((self.session)(connection).into_future(), Instant::now())
+                    },
+                    Err(err) => {
+                        self.retry(err)?;
+                        continue;
+                    }
+                },
+                ReconnectState::Connected(mut future, connected) => match future.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = ReconnectState::Connected(future, connected);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(item)) => return Ok(Async::Ready(item)),
+                    Err(err) => {
+                        if connected.elapsed() >= self.reset_after {
+                            self.strategy.reset();
+                            self.attempt = 0;
+                        }
+                        self.reconnects += 1;
+                        self.retry(err)?;
+                        continue;
+                    }
+                },
+                ReconnectState::Sleeping(mut future) => match future.poll().map_err(RetryError::TimerError)? {
+                    Async::NotReady => {
+                        self.state = ReconnectState::Sleeping(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Async::Ready(_) => ReconnectState::Connecting(self.connect.run())
+                }
+            };
+        }
+
+        task::current().notify();
+        Ok(Async::NotReady)
+    }
+}
+
+#[cfg(test)]
+fn reconnect_delays(reset_after: Duration) -> Vec<Duration> {
+    use futures::future::{ok, FutureResult};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::ExplicitSchedule;
+
+    struct RecordingSleep(Rc<RefCell<Vec<Duration>>>);
+
+    impl Sleep for RecordingSleep {
+        type Future = FutureResult<(), ()>;
+        fn sleep(&mut self, duration: Duration) -> Self::Future {
+            self.0.borrow_mut().push(duration);
+            ok(())
+        }
+    }
+
+    let delays = Rc::new(RefCell::new(Vec::new()));
+    let schedule = ExplicitSchedule::new(vec![Duration::from_millis(1), Duration::from_millis(2), Duration::from_millis(3)]);
+    let mut sessions = 0;
+    let res = ReconnectLoop::new(RecordingSleep(delays.clone()), schedule, || Ok::<_, ()>("connection"), |_| {
+        sessions += 1;
+        if sessions < 3 { Err(()) } else { Ok(42) }
+    }).reset_after(reset_after).wait();
+
+    assert_eq!(res, Ok(42));
+    let delays = delays.borrow().clone();
+    delays
+}
+
+#[test]
+fn backs_off_between_short_lived_connections() {
+    assert_eq!(reconnect_delays(Duration::from_secs(3600)), vec![Duration::from_millis(1), Duration::from_millis(2)]);
+}
+
+#[test]
+fn resets_the_back_off_after_healthy_connections() {
+    assert_eq!(reconnect_delays(Duration::ZERO), vec![Duration::from_millis(1), Duration::from_millis(1)]);
+}
+
+#[test]
+fn yields_between_immediate_reconnects() {
+    use futures::future::lazy;
+    use super::strategy::NoDelay;
+
+    let mut connects = 0;
+    let mut reconnect = ReconnectLoop::new(tokio_timer::Timer::default(), NoDelay, || {
+        connects += 1;
+        Err::<(), _>(connects)
+    }, |_| Ok::<(), u64>(())).yield_after(3);
+
+    let polled = lazy(|| Ok::<_, ()>(reconnect.poll())).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    drop(reconnect);
+    assert_eq!(connects, 2);
+}
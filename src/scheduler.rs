@@ -0,0 +1,207 @@
+use futures::{Async, Future, Poll};
+use futures::task::AtomicTask;
+use std::convert::Infallible;
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::Sleep;
+
+/// Sleeps on a single, coarse timer wheel shared by all of its clones, trading precision
+/// for throughput when tens of thousands of retry sequences are sleeping at once.
+///
+/// Delays are rounded up to whole ticks of the wheel, counted from the next tick, so that
+/// sleeps never fire early, but up to two ticks late. All sleeps expiring within
+/// the same tick are woken in one batch by a background thread, which exits once the
+/// scheduler and all of its pending sleeps have been dropped.
+#[derive(Clone)]
+pub struct RetryScheduler {
+    wheel: Arc<Wheel>
+}
+
+impl fmt::Debug for RetryScheduler {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("RetryScheduler")
+            .field("tick", &self.wheel.tick)
+            .finish_non_exhaustive()
+    }
+}
+
+struct Wheel {
+    tick: Duration,
+    state: Mutex<WheelState>
+}
+
+struct WheelState {
+    cursor: usize,
+    slots: Vec<Vec<Entry>>
+}
+
+/// A pending sleep, which fires once its slot has been visited `rounds` more times.
+struct Entry {
+    rounds: usize,
+    wakeup: Arc<Wakeup>
+}
+
+struct Wakeup {
+    fired: AtomicBool,
+    task: AtomicTask
+}
+
+impl Wheel {
+    fn schedule(&self, duration: Duration) -> Arc<Wakeup> {
+        let ticks = ticks(duration, self.tick);
+        let wakeup = Arc::new(Wakeup{fired: AtomicBool::new(ticks == 0), task: AtomicTask::new()});
+        if ticks > 0 {
+            let mut state = self.state.lock().unwrap();
+            let len = state.slots.len();
+            // The current tick has partially elapsed already, so the sleep only starts
+            // counting with the next one, lest it fire up to a tick early.
+            let slot = (state.cursor + ticks + 1) % len;
+            state.slots[slot].push(Entry{rounds: ticks / len, wakeup: wakeup.clone()});
+        }
+        wakeup
+    }
+
+    fn advance(&self) {
+        let expired = {
+            let mut state = self.state.lock().unwrap();
+            state.cursor = (state.cursor + 1) % state.slots.len();
+            let cursor = state.cursor;
+            let (expired, pending) = mem::take(&mut state.slots[cursor]).into_iter()
+                .partition::<Vec<_>, _>(|entry| entry.rounds == 0);
+            state.slots[cursor] = pending.into_iter()
+                .map(|entry| Entry{rounds: entry.rounds - 1, wakeup: entry.wakeup})
+                .collect();
+            expired
+        };
+        for entry in expired {
+            entry.wakeup.fired.store(true, Ordering::SeqCst);
+            entry.wakeup.task.notify();
+        }
+    }
+}
+
+/// Returns the number of ticks to wait for the given duration, rounded up.
+fn ticks(duration: Duration, tick: Duration) -> usize {
+    let ticks = duration.as_nanos().div_ceil(tick.as_nanos().max(1));
+    ticks.min(usize::MAX as u128) as usize
+}
+
+impl RetryScheduler {
+    /// Constructs a scheduler whose wheel advances every `tick`, and has `slots` slots.
+    ///
+    /// Delays longer than `tick * slots` are supported, but are revisited once per revolution
+    /// of the wheel, so `slots` should cover the typical delays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick` is zero or `slots` is zero.
+    pub fn new(tick: Duration, slots: usize) -> RetryScheduler {
+        assert!(tick > Duration::ZERO && slots > 0, "RetryScheduler requires a non-zero tick and number of slots");
+        let wheel = Arc::new(Wheel{tick, state: Mutex::new(WheelState{cursor: 0, slots: (0..slots).map(|_| Vec::new()).collect()})});
+        let weak = Arc::downgrade(&wheel);
+        thread::Builder::new()
+            .name("tokio-retry-scheduler".to_string())
+            .spawn(move || run(weak, tick))
+            .expect("failed to spawn the scheduler thread");
+        RetryScheduler{wheel}
+    }
+
+    /// Returns the tick of the wheel, i.e. the precision of its delays.
+    pub fn tick(&self) -> Duration {
+        self.wheel.tick
+    }
+}
+
+fn run(wheel: Weak<Wheel>, tick: Duration) {
+    let mut next = Instant::now() + tick;
+    loop {
+        let now = Instant::now();
+        if next > now {
+            thread::sleep(next - now);
+        }
+        next += tick;
+        match wheel.upgrade() {
+            Some(wheel) => wheel.advance(),
+            None => return
+        }
+    }
+}
+
+impl Sleep for RetryScheduler {
+    type Future = ScheduledSleep;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Future {
+        ScheduledSleep{wakeup: self.wheel.schedule(duration), _wheel: self.wheel.clone()}
+    }
+}
+
+/// Future of a sleep on a [`RetryScheduler`](struct.RetryScheduler.html).
+pub struct ScheduledSleep {
+    wakeup: Arc<Wakeup>,
+    // Keeps the wheel turning until the sleep has completed or is dropped.
+    _wheel: Arc<Wheel>
+}
+
+impl Future for ScheduledSleep {
+    type Item = ();
+    type Error = Infallible;
+
+    fn poll(&mut self) -> Poll<(), Infallible> {
+        self.wakeup.task.register();
+        if self.wakeup.fired.load(Ordering::SeqCst) {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+#[test]
+fn rounds_delays_up_to_ticks() {
+    let tick = Duration::from_millis(10);
+    assert_eq!(ticks(Duration::ZERO, tick), 0);
+    assert_eq!(ticks(Duration::from_millis(1), tick), 1);
+    assert_eq!(ticks(Duration::from_millis(10), tick), 1);
+    assert_eq!(ticks(Duration::from_millis(25), tick), 3);
+}
+
+#[test]
+fn wakes_sleeps_beyond_a_revolution() {
+    let mut scheduler = RetryScheduler::new(Duration::from_millis(5), 4);
+    let started = Instant::now();
+    let sleeps: Vec<_> = [0, 10, 30].iter().map(|&millis| scheduler.sleep(Duration::from_millis(millis))).collect();
+
+    for sleep in sleeps {
+        assert_eq!(sleep.wait(), Ok(()));
+    }
+    assert!(started.elapsed() >= Duration::from_millis(30));
+}
+
+#[test]
+fn drives_retry_futures() {
+    use super::RetryFuture;
+    use super::strategy::FixedInterval;
+
+    let mut attempts = 0;
+    let res = RetryFuture::spawn(RetryScheduler::new(Duration::from_millis(5), 16), FixedInterval::new(Duration::from_millis(20)).take(2), || {
+        attempts += 1;
+        if attempts < 3 { Err(()) } else { Ok(attempts) }
+    }).without_timer_errors().wait();
+
+    assert_eq!(res, Ok(3));
+}
+
+#[test]
+fn never_wakes_sleeps_early() {
+    let mut scheduler = RetryScheduler::new(Duration::from_millis(20), 8);
+    thread::sleep(Duration::from_millis(30));
+    let started = Instant::now();
+
+    assert_eq!(scheduler.sleep(Duration::from_millis(20)).wait(), Ok(()));
+    assert!(started.elapsed() >= Duration::from_millis(20));
+}
@@ -0,0 +1,247 @@
+use futures::{task, Async, AsyncSink, Future, Poll, Sink, StartSend};
+use std::cmp;
+use std::time::Instant;
+
+use super::{RetryError, Sleep};
+use super::future::DEFAULT_YIELD_AFTER;
+use super::strategy::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+type SinkError<S, Si> = RetryError<<Si as Sink>::SinkError, <<S as Sleep>::Future as Future>::Error>;
+
+/// Sink that forwards items to an inner sink, and recreates the inner sink via a factory
+/// whenever sending or flushing fails, after the delay of the strategy.
+///
+/// The item in flight is buffered until the inner sink has flushed it, and replayed to the
+/// recreated sink, so items are delivered in order and at least once. Only one item is in
+/// flight at a time, which is why items need to be `Clone`. The strategy is reset once an
+/// item has been flushed, so that only consecutive failures back off.
+///
+/// Sending or flushing fails with `RetryError::RetriesExhausted`, discarding the item in
+/// flight, should the strategy give up.
+pub struct RetrySink<S: Sleep, I, F, Si: Sink> {
+    sleep: S,
+    strategy: I,
+    factory: F,
+    sink: Option<Si>,
+    sleeping: Option<S::Future>,
+    pending: Option<Si::SinkItem>,
+    accepted: bool,
+    yield_after: usize,
+    attempt: usize,
+    started: Instant
+}
+
+impl<S, I, F, Si> RetrySink<S, I, F, Si>
+    where S: Sleep, I: BackoffStrategy<Si::SinkError> + ResettableStrategy, F: FnMut() -> Si, Si: Sink, Si::SinkItem: Clone {
+    /// Constructs a retry sink, creating the inner sink via the factory.
+    pub fn new<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, factory: F) -> RetrySink<S, I, F, Si> {
+        RetrySink{
+            sleep,
+            strategy: strategy.into_strategy(),
+            factory,
+            sink: None,
+            sleeping: None,
+            pending: None,
+            accepted: false,
+            yield_after: DEFAULT_YIELD_AFTER,
+            attempt: 0,
+            started: Instant::now()
+        }
+    }
+
+    /// Yields to the executor after the given number of consecutive state transitions
+    /// within a single poll, which defaults to 64, see `RetryFuture::yield_after`.
+    pub fn yield_after(mut self, transitions: usize) -> RetrySink<S, I, F, Si> {
+        self.yield_after = cmp::max(transitions, 1);
+        self
+    }
+
+    /// Returns the number of consecutive failures of the inner sink so far.
+    pub fn failures(&self) -> usize {
+        self.attempt
+    }
+
+    fn fail(&mut self, err: Si::SinkError) -> Result<(), SinkError<S, Si>> {
+        self.sink = None;
+        self.accepted = false;
+        if self.attempt == 0 {
+            self.started = Instant::now();
+        }
+        self.attempt += 1;
+        match self.strategy.delay(self.attempt, &err) {
+            Some(delay) => {
+                self.sleeping = Some(self.sleep.sleep(delay));
+                Ok(())
+            },
+            None => {
+                self.pending = None;
+                Err(RetryError::RetriesExhausted{
+                    last_error: err,
+                    attempts: self.attempt,
+                    total_elapsed: self.started.elapsed(),
+                    recent_errors: Vec::new(),
+                    attempts_exhausted: self.strategy.attempts_exhausted()
+                })
+            }
+        }
+    }
+
+    /// Drives the item in flight, if any, until it has been flushed by an inner sink.
+    fn drive(&mut self) -> Poll<(), SinkError<S, Si>> {
+        for _ in 0..self.yield_after {
+            if let Some(mut sleeping) = self.sleeping.take() {
+                if sleeping.poll().map_err(RetryError::TimerError)?.is_not_ready() {
+                    self.sleeping = Some(sleeping);
+                    return Ok(Async::NotReady);
+                }
+            }
+            let item = match self.pending {
+                Some(ref item) if !self.accepted => Some(item.clone()),
+                Some(_) => None,
+                None => return Ok(Async::Ready(()))
+            };
+            let factory = &mut self.factory;
+            let sink = self.sink.get_or_insert_with(factory);
+            if let Some(item) = item {
+                match sink.start_send(item) {
+                    Ok(AsyncSink::Ready) => self.accepted = true,
+                    Ok(AsyncSink::NotReady(_)) => return Ok(Async::NotReady),
+                    Err(err) => {
+                        self.fail(err)?;
+                        continue;
+                    }
+                }
+            }
+            match sink.poll_complete() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(())) => {
+                    self.pending = None;
+                    self.accepted = false;
+                    if self.attempt > 0 {
+                        self.strategy.reset();
+                        self.attempt = 0;
+                    }
+                },
+                Err(err) => self.fail(err)?
+            }
+        }
+
+        task::current().notify();
+        Ok(Async::NotReady)
+    }
+}
+
+impl<S, I, F, Si> Sink for RetrySink<S, I, F, Si>
+    where S: Sleep, I: BackoffStrategy<Si::SinkError> + ResettableStrategy, F: FnMut() -> Si, Si: Sink, Si::SinkItem: Clone {
+    type SinkItem = Si::SinkItem;
+    type SinkError = SinkError<S, Si>;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        if self.drive()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item));
+        }
+        self.pending = Some(item);
+        self.drive()?;
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.drive()
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        if self.drive()?.is_not_ready() {
+            return Ok(Async::NotReady);
+        }
+        match self.sink {
+            Some(ref mut sink) => sink.close().map_err(RetryError::OperationError),
+            None => Ok(Async::Ready(()))
+        }
+    }
+}
+
+#[cfg(test)]
+struct FlakySink {
+    sent: std::rc::Rc<std::cell::RefCell<Vec<u64>>>,
+    buffer: Option<u64>,
+    fail: bool
+}
+
+#[cfg(test)]
+impl Sink for FlakySink {
+    type SinkItem = u64;
+    type SinkError = &'static str;
+
+    fn start_send(&mut self, item: u64) -> StartSend<u64, &'static str> {
+        self.buffer = Some(item);
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), &'static str> {
+        if self.fail {
+            return Err("connection reset");
+        }
+        self.sent.borrow_mut().extend(self.buffer.take());
+        Ok(Async::Ready(()))
+    }
+}
+
+#[test]
+fn replays_items_to_recreated_sinks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let mut sinks = 0;
+    let sink = RetrySink::new(tokio_timer::Timer::default(), NoDelay.max_attempts(2), || {
+        sinks += 1;
+        FlakySink{sent: sent.clone(), buffer: None, fail: sinks % 2 == 1}
+    });
+
+    let sink = sink.send(1).wait().unwrap();
+    assert_eq!(sink.failures(), 0);
+    drop(sink.send(2).wait().unwrap());
+    assert_eq!(*sent.borrow(), vec![1, 2]);
+}
+
+#[test]
+fn fails_once_consecutive_failures_are_exhausted() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let sink = RetrySink::new(tokio_timer::Timer::default(), NoDelay.max_attempts(2), || {
+        FlakySink{sent: sent.clone(), buffer: None, fail: true}
+    });
+
+    match sink.send(1).wait() {
+        Err(RetryError::RetriesExhausted{last_error, attempts, ..}) => {
+            assert_eq!(last_error, "connection reset");
+            assert_eq!(attempts, 2);
+        },
+        _ => panic!("retries were not exhausted")
+    }
+    assert!(sent.borrow().is_empty());
+}
+
+#[test]
+fn yields_between_immediate_failures() {
+    use futures::future::lazy;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::strategy::NoDelay;
+
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let mut sinks = 0;
+    let mut sink = RetrySink::new(tokio_timer::Timer::default(), NoDelay, || {
+        sinks += 1;
+        FlakySink{sent: sent.clone(), buffer: None, fail: true}
+    }).yield_after(3);
+
+    let polled = lazy(|| Ok::<_, ()>(sink.start_send(1).and_then(|_| sink.poll_complete()))).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    drop(sink);
+    assert!(sinks > 1);
+}
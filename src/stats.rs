@@ -0,0 +1,116 @@
+use futures::{Async, Future, Poll};
+use std::collections::vec_deque;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryFuture, Sleep};
+use super::condition::Condition;
+use super::hook::RetryHook;
+use super::strategy::BackoffStrategy;
+
+/// The number of the most recent attempts whose latencies are kept in `RetryStats`.
+pub(crate) const MAX_LATENCIES: usize = 64;
+
+/// Statistics about the attempts made by a `RetryFuture`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RetryStats {
+    attempts: usize,
+    latencies: Vec<Duration>,
+    total_delay: Duration,
+    total_elapsed: Duration
+}
+
+impl RetryStats {
+    pub(crate) fn new(attempts: usize, latencies: Vec<Duration>, total_delay: Duration, total_elapsed: Duration) -> RetryStats {
+        RetryStats{attempts, latencies, total_delay, total_elapsed}
+    }
+
+    /// Returns the number of attempts made, including the initial one.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Returns how long each completed attempt took, oldest first.
+    ///
+    /// Only the latencies of the 64 most recent attempts are kept, which keeps memory
+    /// bounded even for strategies which retry forever.
+    pub fn latencies(&self) -> &[Duration] {
+        &self.latencies
+    }
+
+    /// Returns the total time slept between attempts.
+    pub fn total_delay(&self) -> Duration {
+        self.total_delay
+    }
+
+    /// Returns the time elapsed since the initial attempt was started.
+    pub fn total_elapsed(&self) -> Duration {
+        self.total_elapsed
+    }
+}
+
+/// The result of a successful retry, along with statistics about the attempts it took.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Outcome<T, E> {
+    /// The result of the successful attempt.
+    pub value: T,
+    /// Statistics about the attempts made.
+    pub stats: RetryStats,
+    /// The errors of the failed attempts before the successful one, oldest first,
+    /// as far as kept via `RetryFuture::keep_errors`.
+    pub recent_errors: Vec<E>
+}
+
+/// Future that resolves to the [`Outcome`](struct.Outcome.html) of a `RetryFuture`.
+///
+/// This `struct` is created by the `with_stats` method on `RetryFuture`.
+pub struct WithStats<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    inner: RetryFuture<S, I, A, C, H>
+}
+
+impl<S, I, A, C, H> WithStats<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    pub(crate) fn new(inner: RetryFuture<S, I, A, C, H>) -> WithStats<S, I, A, C, H> {
+        WithStats{inner}
+    }
+
+    /// Returns statistics about the attempts made so far.
+    pub fn stats(&self) -> RetryStats {
+        self.inner.stats()
+    }
+
+    /// Returns the kept errors of the most recent failed attempts, oldest first.
+    pub fn recent_errors(&self) -> vec_deque::Iter<'_, A::Error> {
+        self.inner.recent_errors()
+    }
+}
+
+impl<S, I, A, C, H> Future for WithStats<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    type Item = Outcome<A::Item, A::Error>;
+    type Error = <RetryFuture<S, I, A, C, H> as Future>::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll()? {
+            Async::NotReady => Ok(Async::NotReady),
+            Async::Ready(value) => Ok(Async::Ready(Outcome{
+                value,
+                stats: self.inner.stats(),
+                recent_errors: self.inner.take_recent_errors()
+            }))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializes_statistics() {
+    extern crate serde_json;
+    let stats = RetryStats::new(2, vec![Duration::from_millis(5)], Duration::from_secs(1), Duration::from_secs(2));
+
+    assert_eq!(
+        serde_json::to_string(&stats).unwrap(),
+        r#"{"attempts":2,"latencies":[{"secs":0,"nanos":5000000}],"total_delay":{"secs":1,"nanos":0},"total_elapsed":{"secs":2,"nanos":0}}"#
+    );
+}
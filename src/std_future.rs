@@ -0,0 +1,172 @@
+use futures::{self, task, Async};
+use futures::executor::{self, Notify, NotifyHandle};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use super::{Action, RetryError, RetryFuture};
+use super::compat::{self, FromStd};
+use super::future::Sleep;
+use super::condition::Condition;
+use super::hook::RetryHook;
+use super::strategy::{BackoffStrategy, IntoStrategy};
+
+/// Wakes a `std` task when a futures 0.1 future becomes ready.
+struct WakerNotify(Waker);
+
+impl Notify for WakerNotify {
+    fn notify(&self, _: usize) {
+        self.0.wake_by_ref();
+    }
+}
+
+/// Notifies a futures 0.1 task when a `std` future becomes ready.
+struct TaskWaker(task::Task);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.notify();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.notify();
+    }
+}
+
+/// Polls a futures 0.1 future from within a `std` task.
+pub(crate) fn poll_01<F: futures::Future>(future: &mut F, cx: &mut Context) -> Poll<Result<F::Item, F::Error>> {
+    let notify = NotifyHandle::from(Arc::new(WakerNotify(cx.waker().clone())));
+    match executor::spawn(future).poll_future_notify(&notify, 0) {
+        Ok(Async::Ready(item)) => Poll::Ready(Ok(item)),
+        Ok(Async::NotReady) => Poll::Pending,
+        Err(err) => Poll::Ready(Err(err))
+    }
+}
+
+/// Polls a `std` future from within a futures 0.1 task.
+pub(crate) fn poll_std<F: Future + ?Sized>(future: Pin<&mut F>) -> Async<F::Output> {
+    let waker = Waker::from(Arc::new(TaskWaker(task::current())));
+    match future.poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => Async::Ready(output),
+        Poll::Pending => Async::NotReady
+    }
+}
+
+/// An action driven by a closure returning a `std` future, such as an `async` block.
+///
+/// This `struct` is created by `RetryFuture::spawn_async`.
+pub struct AsyncAction<F> {
+    f: F
+}
+
+impl<F> AsyncAction<F> {
+    pub(crate) fn new(f: F) -> AsyncAction<F> {
+        AsyncAction{f}
+    }
+}
+
+impl<T, E, R: Future<Output=Result<T, E>>, F: FnMut() -> R> Action for AsyncAction<F> {
+    type Item = T;
+    type Error = E;
+    type Future = AsyncAttempt<R>;
+
+    fn run(&mut self) -> Self::Future {
+        compat::from_std((self.f)())
+    }
+}
+
+/// A single attempt of an [`AsyncAction`](struct.AsyncAction.html).
+pub type AsyncAttempt<R> = FromStd<R>;
+
+impl<S, I, F, R> RetryFuture<S, I, AsyncAction<F>> where S: Sleep, AsyncAction<F>: Action, I: BackoffStrategy<<AsyncAction<F> as Action>::Error>, F: FnMut() -> R, R: Future {
+    /// Like `spawn`, but retries a closure returning a `std` future, such as an `async` block.
+    ///
+    /// Like every `RetryFuture`, the result can be `.await`ed directly:
+    ///
+    /// ```rust,edition2018
+    /// use std::time::Duration;
+    /// use tokio_timer::Timer;
+    /// use tokio_retry::{RetryError, RetryFuture};
+    /// use tokio_retry::strategy::FixedInterval;
+    ///
+    /// async fn fetch() -> Result<u64, ()> {
+    ///     // do some real-world stuff here...
+    ///     Ok(42)
+    /// }
+    ///
+    /// async fn fetch_with_retries() -> Result<u64, RetryError<(), tokio_timer::TimerError>> {
+    ///     let strategy = FixedInterval::new(Duration::from_millis(100)).take(3);
+    ///     RetryFuture::spawn_async(Timer::default(), strategy, fetch).await
+    /// }
+    /// ```
+    pub fn spawn_async<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, f: F) -> RetryFuture<S, I, AsyncAction<F>> {
+        RetryFuture::spawn(sleep, strategy, AsyncAction::new(f))
+    }
+}
+
+// Futures 0.1 are polled by mutable reference and may be moved in between polls,
+// so none of the fields of a `RetryFuture` is structurally pinned.
+impl<S, I, A, C, H> Unpin for RetryFuture<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {}
+
+impl<S, I, A, C, H> Future for RetryFuture<S, I, A, C, H> where S: Sleep, I: BackoffStrategy<A::Error>, A: Action, C: Condition<A::Error>, H: RetryHook<A::Error> {
+    type Output = Result<A::Item, RetryError<A::Error, <S::Future as futures::Future>::Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        poll_01(self.get_mut(), cx)
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park()
+        }
+    }
+}
+
+#[test]
+fn retries_std_futures() {
+    use std::future::ready;
+    use tokio_timer::Timer;
+    use super::strategy::NoDelay;
+
+    let mut attempts = 0;
+    let future = RetryFuture::spawn_async(Timer::default(), NoDelay.take(5), || {
+        attempts += 1;
+        ready(if attempts < 3 { Err(attempts) } else { Ok(attempts) })
+    });
+
+    assert_eq!(block_on(future), Ok(3));
+}
+
+#[test]
+fn wakes_the_std_task_after_sleeping() {
+    use std::future::ready;
+    use std::time::Duration;
+    use tokio_timer::Timer;
+    use super::strategy::FixedInterval;
+
+    let mut attempts = 0;
+    let future = RetryFuture::spawn_async(Timer::default(), FixedInterval::new(Duration::from_millis(200)).take(1), || {
+        attempts += 1;
+        ready(if attempts < 2 { Err(()) } else { Ok(attempts) })
+    });
+
+    assert_eq!(block_on(future), Ok(2));
+}
@@ -0,0 +1,121 @@
+use std::cmp;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{saturating_mul, BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// An adaptive retry strategy driven by additive-increase/multiplicative-decrease.
+///
+/// Every failure multiplies the delay by a factor, and every success shortens it
+/// by a fixed step, within the configured bounds. Clones share their state, so
+/// a single `AimdBackoff` can pace all retries against the same downstream.
+///
+/// The strategy never gives up on its own.
+#[derive(Clone, Debug)]
+pub struct AimdBackoff {
+    state: Arc<Mutex<AimdState>>
+}
+
+#[derive(Debug)]
+struct AimdState {
+    current: Duration,
+    min: Duration,
+    max: Duration,
+    factor: f64,
+    step: Duration
+}
+
+impl AimdBackoff {
+    /// Constructs a new AIMD strategy, starting at `min` and never exceeding `max`.
+    ///
+    /// By default, failures double the delay and successes shorten it by `min`.
+    pub fn new(min: Duration, max: Duration) -> AimdBackoff {
+        AimdBackoff{state: Arc::new(Mutex::new(AimdState{current: min, min, max, factor: 2.0, step: min}))}
+    }
+
+    /// Sets the multiplier applied to the delay after each failure.
+    pub fn factor(self, factor: f64) -> AimdBackoff {
+        self.state.lock().unwrap().factor = factor;
+        self
+    }
+
+    /// Sets the amount by which the delay is shortened after each success.
+    pub fn step(self, step: Duration) -> AimdBackoff {
+        self.state.lock().unwrap().step = step;
+        self
+    }
+
+    /// Returns the delay which would be used after the next failure.
+    pub fn current_delay(&self) -> Duration {
+        self.state.lock().unwrap().current
+    }
+}
+
+impl<E> BackoffStrategy<E> for AimdBackoff {
+    fn delay(&mut self, _: usize, _: &E) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let duration = state.current;
+
+        state.current = cmp::min(saturating_mul(duration, state.factor), state.max);
+
+        Some(duration)
+    }
+
+    fn succeeded(&mut self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.current = cmp::max(state.current.checked_sub(state.step).unwrap_or(Duration::ZERO), state.min);
+    }
+}
+
+impl IntoStrategy for AimdBackoff {
+    type Strategy = AimdBackoff;
+
+    fn into_strategy(self) -> AimdBackoff {
+        self
+    }
+}
+
+impl fmt::Display for AimdBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let state = self.state.lock().unwrap();
+        write!(formatter, "aimd(min={:?}, max={:?}, factor={}, step={:?})", state.min, state.max, state.factor, state.step)
+    }
+}
+
+impl ResettableStrategy for AimdBackoff {
+    fn reset(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.current = state.min;
+    }
+}
+
+#[test]
+fn increases_multiplicatively_on_failure() {
+    let mut s = AimdBackoff::new(Duration::from_millis(100), Duration::from_millis(500));
+
+    assert_eq!(s.delay(1, &()), Some(Duration::from_millis(100)));
+    assert_eq!(s.delay(2, &()), Some(Duration::from_millis(200)));
+    assert_eq!(s.delay(3, &()), Some(Duration::from_millis(400)));
+    assert_eq!(s.delay(4, &()), Some(Duration::from_millis(500)));
+}
+
+#[test]
+fn decreases_additively_on_success() {
+    let mut s = AimdBackoff::new(Duration::from_millis(100), Duration::from_secs(10));
+    let shared = s.clone();
+
+    s.delay(1, &());
+    s.delay(2, &());
+    s.delay(3, &());
+    assert_eq!(shared.current_delay(), Duration::from_millis(800));
+
+    BackoffStrategy::<()>::succeeded(&mut s);
+    assert_eq!(shared.current_delay(), Duration::from_millis(700));
+
+    for _ in 0..10 {
+        BackoffStrategy::<()>::succeeded(&mut s);
+    }
+    assert_eq!(shared.current_delay(), Duration::from_millis(100));
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy which schedules retries at the next multiple of a fixed period.
+///
+/// Periods are counted from a shared epoch, the Unix epoch by default, rather than
+/// from the time of the failure. This aligns retries with e.g. quotas refreshing
+/// on minute boundaries.
+#[derive(Clone, Debug)]
+pub struct AlignedInterval {
+    period: Duration,
+    epoch: SystemTime
+}
+
+impl AlignedInterval {
+    /// Constructs a new aligned interval strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> AlignedInterval {
+        assert!(period > Duration::ZERO, "AlignedInterval: period must not be zero");
+        AlignedInterval{period, epoch: UNIX_EPOCH}
+    }
+
+    /// Sets the epoch the periods are counted from.
+    pub fn epoch(mut self, epoch: SystemTime) -> AlignedInterval {
+        self.epoch = epoch;
+        self
+    }
+
+    fn delay_at(&self, now: SystemTime) -> Duration {
+        let period = self.period.as_nanos();
+        let offset = match now.duration_since(self.epoch) {
+            Ok(elapsed) => elapsed.as_nanos() % period,
+            Err(err) => (period - err.duration().as_nanos() % period) % period
+        };
+
+        let nanos = period - offset;
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+impl Iterator for AlignedInterval {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        Some(self.delay_at(SystemTime::now()))
+    }
+}
+
+impl fmt::Display for AlignedInterval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "aligned(period={:?})", self.period)
+    }
+}
+
+impl ResettableStrategy for AlignedInterval {
+    fn reset(&mut self) {}
+}
+
+#[test]
+fn waits_until_the_next_boundary() {
+    let s = AlignedInterval::new(Duration::from_secs(60));
+
+    assert_eq!(s.delay_at(UNIX_EPOCH + Duration::from_secs(3 * 60 + 45)), Duration::from_secs(15));
+    assert_eq!(s.delay_at(UNIX_EPOCH + Duration::from_secs(3 * 60)), Duration::from_secs(60));
+}
+
+#[test]
+fn counts_periods_from_the_epoch() {
+    let epoch = UNIX_EPOCH + Duration::from_secs(10);
+    let s = AlignedInterval::new(Duration::from_secs(60)).epoch(epoch);
+
+    assert_eq!(s.delay_at(UNIX_EPOCH + Duration::from_secs(65)), Duration::from_secs(5));
+    assert_eq!(s.delay_at(UNIX_EPOCH), Duration::from_secs(10));
+}
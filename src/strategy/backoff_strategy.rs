@@ -0,0 +1,62 @@
+use std::time::Duration;
+use std::iter::{Iterator, IntoIterator};
+
+/// A retry strategy as consulted by `RetryFuture`.
+///
+/// Every `Iterator<Item=Duration>` is a strategy which ignores the outcome of attempts.
+/// Implement this trait directly for strategies which adapt to how attempts turn out,
+/// or to the errors of type `E` they produce.
+pub trait BackoffStrategy<E> {
+    /// Returns the delay before the next attempt, or `None` to stop retrying.
+    ///
+    /// This is called with the error of every failed attempt, along with the number
+    /// of that attempt, starting at 1 for the initial one.
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration>;
+
+    /// Called once an attempt has succeeded.
+    fn succeeded(&mut self) {}
+
+    /// Returns whether the strategy stopped retrying because the maximum number of attempts
+    /// was reached, see `StrategyExt::max_attempts`, rather than e.g. because it ran out of time.
+    ///
+    /// This is consulted once `delay` returned `None`, and reported as the `attempts_exhausted`
+    /// flag of `RetryError::RetriesExhausted`.
+    fn attempts_exhausted(&self) -> bool {
+        false
+    }
+}
+
+impl<E, I: Iterator<Item=Duration>> BackoffStrategy<E> for I {
+    fn delay(&mut self, _: usize, _: &E) -> Option<Duration> {
+        self.next()
+    }
+}
+
+/// A retry strategy which can be rewound to its initial state.
+///
+/// This allows long-lived retry loops, such as reconnect loops, to start over
+/// after a period of stability without reconstructing the strategy.
+pub trait ResettableStrategy {
+    /// Resets the strategy, as if no attempts had been made yet.
+    fn reset(&mut self);
+}
+
+/// Conversion into a [`BackoffStrategy`](trait.BackoffStrategy.html).
+///
+/// This is implemented for everything that can be turned into an `Iterator<Item=Duration>`,
+/// so that e.g. a `Vec<Duration>` can be passed to `RetryFuture::spawn` directly.
+pub trait IntoStrategy {
+    /// The strategy produced by the conversion.
+    type Strategy;
+
+    /// Converts the value into a strategy.
+    fn into_strategy(self) -> Self::Strategy;
+}
+
+impl<T: IntoIterator<Item=Duration>> IntoStrategy for T {
+    type Strategy = T::IntoIter;
+
+    fn into_strategy(self) -> Self::Strategy {
+        self.into_iter()
+    }
+}
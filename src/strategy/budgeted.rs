@@ -0,0 +1,149 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// The number of units a token is divided into, so that fractional deposits add up exactly.
+const TOKEN: u64 = 1000;
+
+/// A token bucket limiting the aggregate rate of retries across all calls sharing it.
+///
+/// Every retry withdraws one token, and every success deposits a fraction of one, up to
+/// the capacity of the bucket. When a dependency is hard-down, successes stop and the bucket
+/// drains, so that retries are bounded to a ratio of the successful calls instead of multiplying
+/// the load. Clones share the same bucket.
+#[derive(Clone, Debug)]
+pub struct RetryBudget {
+    state: Arc<Mutex<BudgetState>>
+}
+
+/// The balance, capacity and deposit, in thousandths of a token.
+#[derive(Debug)]
+struct BudgetState {
+    balance: u64,
+    capacity: u64,
+    deposit: u64
+}
+
+impl RetryBudget {
+    /// Constructs a full budget, holding up to `capacity` retries, and regaining
+    /// `ratio` retries per success, e.g. `0.1` to allow one retry per ten successes.
+    ///
+    /// The ratio is rounded to thousandths of a retry.
+    pub fn new(capacity: usize, ratio: f64) -> RetryBudget {
+        let capacity = capacity as u64 * TOKEN;
+        let deposit = (ratio.max(0.0) * TOKEN as f64).round() as u64;
+        RetryBudget{state: Arc::new(Mutex::new(BudgetState{balance: capacity, capacity, deposit}))}
+    }
+
+    /// Returns the number of retries which can currently be made.
+    pub fn available(&self) -> usize {
+        (self.state.lock().unwrap().balance / TOKEN) as usize
+    }
+
+    /// Withdraws a token for a retry, returning whether one was available.
+    pub fn withdraw(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.balance < TOKEN {
+            return false;
+        }
+        state.balance -= TOKEN;
+        true
+    }
+
+    /// Deposits the share of a token earned by a success.
+    pub fn deposit(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.balance = state.balance.saturating_add(state.deposit).min(state.capacity);
+    }
+}
+
+/// A retry strategy adapter which stops retrying once a shared [`RetryBudget`](struct.RetryBudget.html)
+/// is exhausted.
+///
+/// This `struct` is created by the [`budgeted`](fn.budgeted.html) function.
+#[derive(Clone, Debug)]
+pub struct Budgeted<S> {
+    inner: S,
+    budget: RetryBudget
+}
+
+/// Wraps a strategy so that every retry withdraws from the given budget,
+/// and every success deposits to it.
+///
+/// The wrapped strategy still decides the delays, and may stop retrying on its own.
+pub fn budgeted<T: IntoStrategy>(budget: RetryBudget, strategy: T) -> Budgeted<T::Strategy> {
+    Budgeted{inner: strategy.into_strategy(), budget}
+}
+
+impl<E, S: BackoffStrategy<E>> BackoffStrategy<E> for Budgeted<S> {
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration> {
+        let duration = self.inner.delay(attempt, error)?;
+        if self.budget.withdraw() {
+            Some(duration)
+        } else {
+            None
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.budget.deposit();
+        self.inner.succeeded()
+    }
+
+    fn attempts_exhausted(&self) -> bool {
+        self.inner.attempts_exhausted()
+    }
+}
+
+impl<S> IntoStrategy for Budgeted<S> {
+    type Strategy = Budgeted<S>;
+
+    fn into_strategy(self) -> Budgeted<S> {
+        self
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Budgeted<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "budgeted({})", self.inner)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Budgeted<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn stops_retrying_once_the_budget_is_exhausted() {
+    use super::NoDelay;
+
+    let budget = RetryBudget::new(2, 0.5);
+    let mut first = budgeted(budget.clone(), NoDelay);
+    let mut second = budgeted(budget.clone(), NoDelay);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut first, 1, &()), Some(Duration::from_millis(0)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut second, 1, &()), Some(Duration::from_millis(0)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut first, 2, &()), None);
+
+    BackoffStrategy::<()>::succeeded(&mut second);
+    BackoffStrategy::<()>::succeeded(&mut second);
+    assert_eq!(budget.available(), 1);
+    assert_eq!(BackoffStrategy::<()>::delay(&mut first, 1, &()), Some(Duration::from_millis(0)));
+}
+
+#[test]
+fn regains_one_retry_per_ten_successes() {
+    let budget = RetryBudget::new(1, 0.1);
+    assert!(budget.withdraw());
+    assert!(!budget.withdraw());
+
+    for _ in 0..10 {
+        budget.deposit();
+    }
+    assert_eq!(budget.available(), 1);
+    assert!(budget.withdraw());
+}
@@ -0,0 +1,62 @@
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy adapter which caps the delays of another strategy.
+///
+/// This `struct` is created by the [`capped`](trait.StrategyExt.html#method.capped)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Capped<S> {
+    inner: S,
+    max_delay: Duration
+}
+
+impl<S> Capped<S> {
+    pub(crate) fn new(inner: S, max_delay: Duration) -> Capped<S> {
+        Capped{inner, max_delay}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for Capped<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let max_delay = self.max_delay;
+        self.inner.next().map(|duration| cmp::min(duration, max_delay))
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Capped<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "capped({}, max={:?})", self.inner, self.max_delay)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Capped<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn returns_some_capped() {
+    use super::{ExponentialBackoff, StrategyExt};
+    let mut s = ExponentialBackoff::from_millis(10).capped(Duration::from_millis(500));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+}
+
+#[test]
+fn displays_the_inner_strategy() {
+    use super::{FixedInterval, StrategyExt};
+    let s = FixedInterval::new(Duration::from_secs(1)).jittered().capped(Duration::from_millis(500));
+
+    assert_eq!(s.to_string(), "capped(jittered(fixed(interval=1s)), max=500ms)");
+}
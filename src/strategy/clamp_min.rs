@@ -0,0 +1,54 @@
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy adapter which raises the delays of another strategy to a floor.
+///
+/// This `struct` is created by the [`clamp_min`](trait.StrategyExt.html#method.clamp_min)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct ClampMin<S> {
+    inner: S,
+    min_delay: Duration
+}
+
+impl<S> ClampMin<S> {
+    pub(crate) fn new(inner: S, min_delay: Duration) -> ClampMin<S> {
+        ClampMin{inner, min_delay}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for ClampMin<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let min_delay = self.min_delay;
+        self.inner.next().map(|duration| cmp::max(duration, min_delay))
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for ClampMin<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "clamp_min({}, min={:?})", self.inner, self.min_delay)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for ClampMin<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn never_drops_below_the_floor_after_jitter() {
+    use super::{FixedInterval, StrategyExt};
+    let s = FixedInterval::new(Duration::from_millis(100)).jittered().clamp_min(Duration::from_millis(20));
+
+    for duration in s.take(100) {
+        assert!(duration >= Duration::from_millis(20));
+        assert!(duration <= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,125 @@
+use std::cmp;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{equal_jitter, saturating_mul, BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// An exponential back-off coordinated across all tasks targeting the same resource.
+///
+/// Clones share a single count of consecutive failures, so that when one task
+/// observes failures, every task lengthens its delays together. Any success resets
+/// the count. Delays are randomized with [`equal_jitter`](fn.equal_jitter.html)
+/// to avoid synchronized retry storms.
+///
+/// The strategy never gives up on its own.
+#[derive(Clone, Debug)]
+pub struct CoordinatedBackoff {
+    state: Arc<Mutex<CoordinatedState>>
+}
+
+#[derive(Debug)]
+struct CoordinatedState {
+    base: Duration,
+    max: Duration,
+    factor: f64,
+    failures: u32
+}
+
+impl CoordinatedState {
+    fn current(&self) -> Duration {
+        let factor = self.factor.powi(cmp::min(self.failures, i32::MAX as u32) as i32);
+        cmp::min(saturating_mul(self.base, factor), self.max)
+    }
+}
+
+impl CoordinatedBackoff {
+    /// Constructs a new coordinated strategy, starting at `base` and never exceeding `max`.
+    ///
+    /// By default, every failure doubles the delay.
+    pub fn new(base: Duration, max: Duration) -> CoordinatedBackoff {
+        CoordinatedBackoff{state: Arc::new(Mutex::new(CoordinatedState{base, max, factor: 2.0, failures: 0}))}
+    }
+
+    /// Sets the multiplier applied to the delay after each failure.
+    pub fn factor(self, factor: f64) -> CoordinatedBackoff {
+        self.state.lock().unwrap().factor = factor;
+        self
+    }
+
+    /// Returns the number of consecutive failures observed by all tasks.
+    pub fn failures(&self) -> u32 {
+        self.state.lock().unwrap().failures
+    }
+
+    /// Returns the delay, before jitter, which would be used after the next failure.
+    pub fn current_delay(&self) -> Duration {
+        self.state.lock().unwrap().current()
+    }
+}
+
+impl<E> BackoffStrategy<E> for CoordinatedBackoff {
+    fn delay(&mut self, _: usize, _: &E) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let duration = state.current();
+
+        state.failures = state.failures.saturating_add(1);
+
+        Some(equal_jitter(duration))
+    }
+
+    fn succeeded(&mut self) {
+        self.state.lock().unwrap().failures = 0;
+    }
+}
+
+impl IntoStrategy for CoordinatedBackoff {
+    type Strategy = CoordinatedBackoff;
+
+    fn into_strategy(self) -> CoordinatedBackoff {
+        self
+    }
+}
+
+impl fmt::Display for CoordinatedBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let state = self.state.lock().unwrap();
+        write!(formatter, "coordinated(base={:?}, max={:?}, factor={})", state.base, state.max, state.factor)
+    }
+}
+
+impl ResettableStrategy for CoordinatedBackoff {
+    fn reset(&mut self) {
+        self.state.lock().unwrap().failures = 0;
+    }
+}
+
+#[test]
+fn lengthens_the_delays_of_all_clones() {
+    let shared = CoordinatedBackoff::new(Duration::from_millis(100), Duration::from_secs(10));
+    let mut first = shared.clone();
+    let mut second = shared.clone();
+
+    first.delay(1, &());
+    first.delay(2, &());
+    assert_eq!(shared.current_delay(), Duration::from_millis(400));
+
+    let duration = second.delay(1, &()).unwrap();
+    assert!(duration >= Duration::from_millis(200));
+    assert!(duration <= Duration::from_millis(400));
+}
+
+#[test]
+fn resets_all_clones_on_success() {
+    let shared = CoordinatedBackoff::new(Duration::from_millis(100), Duration::from_millis(300));
+    let mut s = shared.clone();
+
+    for attempt in 1..10 {
+        s.delay(attempt, &());
+    }
+    assert_eq!(shared.current_delay(), Duration::from_millis(300));
+
+    BackoffStrategy::<()>::succeeded(&mut s);
+    assert_eq!(shared.failures(), 0);
+    assert_eq!(shared.current_delay(), Duration::from_millis(100));
+}
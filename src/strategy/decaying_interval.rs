@@ -0,0 +1,80 @@
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{saturating_mul, ResettableStrategy};
+
+/// A retry strategy whose delays shrink with every attempt.
+///
+/// This is the inverse of back-off, for waiting on resources which become
+/// more likely to be ready over time.
+#[derive(Clone, Debug)]
+pub struct DecayingInterval {
+    max: Duration,
+    current: Duration,
+    min: Duration,
+    factor: f64
+}
+
+impl DecayingInterval {
+    /// Constructs a new decaying interval strategy,
+    /// starting at `max` and shrinking towards `min`.
+    ///
+    /// The delay is halved after each attempt unless configured otherwise via `factor`.
+    pub fn new(max: Duration, min: Duration) -> DecayingInterval {
+        DecayingInterval{max, current: max, min, factor: 0.5}
+    }
+
+    /// Sets the multiplier applied to the delay after each attempt.
+    ///
+    /// The factor is expected to be between `0` and `1`.
+    pub fn factor(mut self, factor: f64) -> DecayingInterval {
+        self.factor = factor;
+        self
+    }
+}
+
+impl Iterator for DecayingInterval {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = cmp::max(self.current, self.min);
+
+        self.current = saturating_mul(duration, self.factor);
+
+        Some(duration)
+    }
+}
+
+impl fmt::Display for DecayingInterval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "decaying(max={:?}, min={:?}, factor={})", self.max, self.min, self.factor)
+    }
+}
+
+impl ResettableStrategy for DecayingInterval {
+    fn reset(&mut self) {
+        self.current = self.max;
+    }
+}
+
+#[test]
+fn returns_some_decaying() {
+    let mut s = DecayingInterval::new(Duration::from_secs(8), Duration::from_secs(1));
+
+    assert_eq!(s.next(), Some(Duration::from_secs(8)));
+    assert_eq!(s.next(), Some(Duration::from_secs(4)));
+    assert_eq!(s.next(), Some(Duration::from_secs(2)));
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+}
+
+#[test]
+fn returns_some_decaying_with_custom_factor() {
+    let mut s = DecayingInterval::new(Duration::from_millis(1000), Duration::from_millis(100)).factor(0.8);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(1000)));
+    assert_eq!(s.next(), Some(Duration::from_millis(800)));
+    assert_eq!(s.next(), Some(Duration::from_millis(640)));
+}
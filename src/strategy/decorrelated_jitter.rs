@@ -0,0 +1,79 @@
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{DefaultRng, JitterRng, ResettableStrategy};
+
+/// A retry strategy driven by "decorrelated jitter".
+///
+/// Each delay is sampled uniformly between the base and three times
+/// the previous delay, and is capped at a maximum. See the AWS architecture
+/// blog post on [exponential backoff and jitter](https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/).
+#[derive(Clone, Debug)]
+pub struct DecorrelatedJitter {
+    base: Duration,
+    cap: Duration,
+    previous: Duration
+}
+
+impl DecorrelatedJitter {
+    /// Constructs a new decorrelated jitter strategy,
+    /// given a base duration and a cap for the resulting delays.
+    pub fn new(base: Duration, cap: Duration) -> DecorrelatedJitter {
+        DecorrelatedJitter{base, cap, previous: base}
+    }
+}
+
+impl Iterator for DecorrelatedJitter {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let jitter = DefaultRng.next_unit();
+        let low = self.base.as_secs_f64();
+        let high = self.previous.as_secs_f64() * 3.0;
+        let sampled = Duration::try_from_secs_f64(low + (high - low).max(0.0) * jitter)
+            .unwrap_or(Duration::MAX);
+        let duration = cmp::min(sampled, self.cap);
+
+        self.previous = duration;
+
+        Some(duration)
+    }
+}
+
+impl fmt::Display for DecorrelatedJitter {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "decorrelated_jitter(base={:?}, cap={:?})", self.base, self.cap)
+    }
+}
+
+impl ResettableStrategy for DecorrelatedJitter {
+    fn reset(&mut self) {
+        self.previous = self.base;
+    }
+}
+
+#[test]
+fn stays_between_base_and_three_times_previous() {
+    let base = Duration::from_millis(10);
+    let mut s = DecorrelatedJitter::new(base, Duration::from_secs(3600));
+    let mut previous = base;
+
+    for _ in 0..100 {
+        let duration = s.next().unwrap();
+        assert!(duration >= base);
+        assert!(duration <= previous * 3 + Duration::from_nanos(1));
+        previous = duration;
+    }
+}
+
+#[test]
+fn never_exceeds_cap() {
+    let cap = Duration::from_millis(50);
+    let mut s = DecorrelatedJitter::new(Duration::from_millis(10), cap);
+
+    for _ in 0..100 {
+        assert!(s.next().unwrap() <= cap);
+    }
+}
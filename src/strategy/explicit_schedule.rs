@@ -0,0 +1,95 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy driven by an explicit list of delays.
+///
+/// The strategy yields each delay in order, and stops once the list is exhausted.
+#[derive(Clone, Debug)]
+pub struct ExplicitSchedule {
+    delays: Vec<Duration>,
+    position: usize
+}
+
+impl ExplicitSchedule {
+    /// Constructs a new explicit schedule strategy from a list of delays.
+    pub fn new(delays: Vec<Duration>) -> ExplicitSchedule {
+        ExplicitSchedule{delays, position: 0}
+    }
+}
+
+impl From<Vec<Duration>> for ExplicitSchedule {
+    fn from(delays: Vec<Duration>) -> ExplicitSchedule {
+        ExplicitSchedule::new(delays)
+    }
+}
+
+impl<'a> From<&'a [Duration]> for ExplicitSchedule {
+    fn from(delays: &'a [Duration]) -> ExplicitSchedule {
+        ExplicitSchedule::new(delays.to_vec())
+    }
+}
+
+impl Iterator for ExplicitSchedule {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.delays.get(self.position).cloned();
+
+        if duration.is_some() {
+            self.position += 1;
+        }
+
+        duration
+    }
+}
+
+impl fmt::Display for ExplicitSchedule {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "explicit({:?})", self.delays)
+    }
+}
+
+impl ResettableStrategy for ExplicitSchedule {
+    fn reset(&mut self) {
+        self.position = 0;
+    }
+}
+
+#[test]
+fn returns_the_schedule_in_order() {
+    let mut s = ExplicitSchedule::new(vec![
+        Duration::from_secs(1),
+        Duration::from_secs(5),
+        Duration::from_secs(30)
+    ]);
+
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+    assert_eq!(s.next(), Some(Duration::from_secs(5)));
+    assert_eq!(s.next(), Some(Duration::from_secs(30)));
+    assert_eq!(s.next(), None);
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn can_be_built_from_a_slice() {
+    let delays = [Duration::from_millis(10), Duration::from_millis(20)];
+    let mut s = ExplicitSchedule::from(&delays[..]);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn starts_over_on_reset() {
+    let mut s = ExplicitSchedule::new(vec![Duration::from_secs(1), Duration::from_secs(5)]);
+
+    s.next();
+    s.next();
+    s.reset();
+
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+}
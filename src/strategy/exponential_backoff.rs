@@ -1,14 +1,18 @@
+use std::fmt;
 use std::time::Duration;
 use std::iter::Iterator;
-use std::u64::{MAX as U64_MAX};
+
+use super::{saturating_mul, ResettableStrategy};
 
 /// A retry strategy driven by exponential back-off.
 ///
 /// The power corresponds to the number of past attempts.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ExponentialBackoff {
-    current: u64,
-    base: u64
+    base: Duration,
+    current: Duration,
+    factor: f64,
+    max_delay: Option<Duration>
 }
 
 impl ExponentialBackoff {
@@ -18,7 +22,34 @@ impl ExponentialBackoff {
     /// The resulting duration is calculated by taking the base to the `n`-th power,
     /// where `n` denotes the number of past attempts.
     pub fn from_millis(base: u64) -> ExponentialBackoff {
-        ExponentialBackoff{current: base, base: base}
+        ExponentialBackoff{base: Duration::from_millis(base), current: Duration::from_millis(base), factor: base as f64, max_delay: None}
+    }
+
+    /// Constructs a new exponential back-off strategy,
+    /// given the initial delay as a duration.
+    ///
+    /// The delay is doubled after each attempt unless configured otherwise via `factor`.
+    /// Sub-millisecond precision of the initial delay is preserved.
+    pub fn from_duration(initial: Duration) -> ExponentialBackoff {
+        ExponentialBackoff{base: initial, current: initial, factor: 2.0, max_delay: None}
+    }
+
+    /// Sets the multiplier applied to the delay after each attempt,
+    /// replacing the base used by `from_millis`.
+    ///
+    /// The factor does not have to be an integer, e.g. a factor of `1.5`
+    /// grows the delay by half after each attempt.
+    pub fn factor(mut self, factor: f64) -> ExponentialBackoff {
+        self.factor = factor;
+        self
+    }
+
+    /// Caps the delay between retries at the given duration.
+    ///
+    /// Once the cap is reached the strategy keeps yielding it indefinitely.
+    pub fn max_delay(mut self, duration: Duration) -> ExponentialBackoff {
+        self.max_delay = Some(duration);
+        self
     }
 }
 
@@ -26,15 +57,33 @@ impl Iterator for ExponentialBackoff {
     type Item = Duration;
 
     fn next(&mut self) -> Option<Duration> {
-        let duration = Duration::from_millis(self.current);
+        let duration = self.current;
 
-        if let Some(next) = self.current.checked_mul(self.base) {
-            self.current = next;
-        } else {
-            self.current = U64_MAX;
+        if let Some(ref max_delay) = self.max_delay {
+            if duration > *max_delay {
+                return Some(*max_delay);
+            }
         }
 
-        return Some(duration);
+        self.current = saturating_mul(self.current, self.factor);
+
+        Some(duration)
+    }
+}
+
+impl fmt::Display for ExponentialBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "exponential(base={:?}, factor={}", self.base, self.factor)?;
+        if let Some(ref max_delay) = self.max_delay {
+            write!(formatter, ", cap={:?}", max_delay)?;
+        }
+        write!(formatter, ")")
+    }
+}
+
+impl ResettableStrategy for ExponentialBackoff {
+    fn reset(&mut self) {
+        self.current = self.base;
     }
 }
 
@@ -58,9 +107,67 @@ fn returns_some_exponential_base_2() {
 
 #[test]
 fn saturates_at_maximum_value() {
-    let mut s = ExponentialBackoff::from_millis(U64_MAX - 1);
+    let mut s = ExponentialBackoff::from_millis(u64::MAX - 1);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(u64::MAX - 1)));
+    assert_eq!(s.next(), Some(Duration::MAX));
+    assert_eq!(s.next(), Some(Duration::MAX));
+}
+
+#[test]
+fn saturates_at_max_delay_without_overflowing() {
+    let max_delay = Duration::from_secs(60);
+    let mut s = ExponentialBackoff::from_millis(10).max_delay(max_delay);
+
+    for _ in 0..1000 {
+        assert!(s.next().unwrap() <= max_delay);
+    }
+    assert_eq!(s.next(), Some(max_delay));
+}
+
+#[test]
+fn returns_some_exponential_with_fractional_factor() {
+    let mut s = ExponentialBackoff::from_millis(100).factor(1.5);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(150)));
+    assert_eq!(s.next(), Some(Duration::from_millis(225)));
+}
+
+#[test]
+fn preserves_sub_millisecond_precision() {
+    let mut s = ExponentialBackoff::from_duration(Duration::from_micros(1500));
+
+    assert_eq!(s.next(), Some(Duration::from_micros(1500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(3000)));
+    assert_eq!(s.next(), Some(Duration::from_micros(6000)));
+}
+
+#[test]
+fn stops_increasing_at_max_delay() {
+    let mut s = ExponentialBackoff::from_millis(2).max_delay(Duration::from_millis(4));
 
-    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX - 1)));
-    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
-    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
-}
\ No newline at end of file
+    assert_eq!(s.next(), Some(Duration::from_millis(2)));
+    assert_eq!(s.next(), Some(Duration::from_millis(4)));
+    assert_eq!(s.next(), Some(Duration::from_millis(4)));
+    assert_eq!(s.next(), Some(Duration::from_millis(4)));
+}
+
+#[test]
+fn displays_its_parameters() {
+    let s = ExponentialBackoff::from_millis(10).factor(2.0).max_delay(Duration::from_secs(5));
+
+    assert_eq!(s.to_string(), "exponential(base=10ms, factor=2, cap=5s)");
+}
+
+#[test]
+fn rewinds_to_base_on_reset() {
+    let mut s = ExponentialBackoff::from_millis(10);
+
+    s.next();
+    s.next();
+    s.reset();
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+}
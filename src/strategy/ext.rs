@@ -0,0 +1,126 @@
+use std::time::{Duration, Instant};
+use std::iter::Iterator;
+
+use future::Sleep;
+use ticks::Ticks;
+use super::{IntoStrategy, Then, DefaultRng, JitterRng, Jittered, Scaled, Capped, ClampMin, MaxTotalDelay, MaxAttempts, Probing, UntilDeadline, TimeWindow, Windowed};
+
+/// Extension methods for combining retry strategies.
+///
+/// Unlike `Iterator::map`, every combinator returns a nameable adapter type
+/// which stays `Clone` whenever the underlying strategy is, so that it can
+/// be stored in a struct or used with `RetryService`.
+///
+/// Most combinators return iterators again, and compose in any order. `max_attempts` and
+/// `then` return a `BackoffStrategy` which is not an iterator, as it reports more than
+/// delays, so they need to come last in a chain, e.g. `s.jittered().max_attempts(3)`
+/// rather than `s.max_attempts(3).jittered()`.
+pub trait StrategyExt: Iterator<Item=Duration> + Sized {
+    /// Randomizes every delay using [`jitter`](fn.jitter.html).
+    fn jittered(self) -> Jittered<Self> {
+        Jittered::new(self, DefaultRng)
+    }
+
+    /// Randomizes every delay like `jittered`, drawing from the given random number generator.
+    ///
+    /// Passing a seeded generator makes the resulting schedule deterministic,
+    /// which is useful in tests and simulations.
+    fn jitter_with<R: JitterRng>(self, rng: R) -> Jittered<Self, R> {
+        Jittered::new(self, rng)
+    }
+
+    /// Randomizes every delay within the given fraction of its nominal value.
+    ///
+    /// For example, `jitter_pct(0.25)` turns a delay of 100ms into a delay
+    /// between 75ms and 125ms.
+    fn jitter_pct(self, spread: f64) -> Jittered<Self> {
+        Jittered::proportional(self, DefaultRng, spread)
+    }
+
+    /// Multiplies every delay by the given factor.
+    fn scaled(self, factor: f64) -> Scaled<Self> {
+        Scaled::new(self, factor)
+    }
+
+    /// Caps every delay at the given duration.
+    fn capped(self, max_delay: Duration) -> Capped<Self> {
+        Capped::new(self, max_delay)
+    }
+
+    /// Raises every delay to at least the given duration.
+    ///
+    /// Applied after `jittered`, this keeps full jitter from producing
+    /// near-zero delays.
+    fn clamp_min(self, min_delay: Duration) -> ClampMin<Self> {
+        ClampMin::new(self, min_delay)
+    }
+
+    /// Stops yielding delays once their sum would exceed the given budget.
+    ///
+    /// This bounds the total time spent sleeping between attempts,
+    /// independently of the number of attempts.
+    fn max_total_delay(self, budget: Duration) -> MaxTotalDelay<Self> {
+        MaxTotalDelay::new(self, budget)
+    }
+
+    /// Stops yielding delays once the given deadline has passed.
+    ///
+    /// A delay which would end after the deadline is shortened to end at it,
+    /// so no retry is ever scheduled beyond the time the caller has.
+    fn until_deadline(self, deadline: Instant) -> UntilDeadline<Self> {
+        UntilDeadline::new(self, deadline)
+    }
+
+    /// Only permits retries within the given daily wall-clock windows.
+    ///
+    /// A delay which would end outside of every window is extended until the next
+    /// window opens, e.g. to hold off batch retries until business hours.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no window is given.
+    fn within_windows<W: IntoIterator<Item=TimeWindow>>(self, windows: W) -> Windowed<Self> {
+        Windowed::new(self, windows.into_iter().collect())
+    }
+
+    /// Limits the total number of attempts, including the first one.
+    ///
+    /// Unlike `take`, which counts retries, `max_attempts(n)` runs
+    /// the action at most `n` times, i.e. it yields at most `n - 1` delays.
+    ///
+    /// The result is not an iterator, so this needs to come after the other combinators.
+    fn max_attempts(self, attempts: usize) -> MaxAttempts<Self> {
+        MaxAttempts::new(self, attempts)
+    }
+
+    /// Switches to the given strategy once this one is exhausted.
+    ///
+    /// This builds tiered strategies, e.g. a few quick retries before falling back to
+    /// exponential back-off, see the [module documentation](index.html#tiered-strategies).
+    /// Unlike `chain`, the second strategy may adapt to errors, and resetting the result
+    /// starts over with this strategy.
+    ///
+    /// The result is not an iterator, so both tiers need to be combined before, e.g.
+    /// `a.capped(max).then(b.capped(max))` rather than `a.then(b).capped(max)`.
+    fn then<T: IntoStrategy>(self, other: T) -> Then<Self, T::Strategy> {
+        Then::new(self, other.into_strategy())
+    }
+
+    /// Replaces every `every`-th delay with a short probe delay.
+    ///
+    /// Combined with a capped back-off, this keeps a long-lived retry loop
+    /// at a plateau while still detecting recovery quickly.
+    fn with_probe(self, probe: Duration, every: usize) -> Probing<Self> {
+        Probing::new(self, probe, every)
+    }
+
+    /// Turns the strategy into a `Stream` which yields after each delay.
+    ///
+    /// This is useful to drive custom retry or polling loops with the same
+    /// schedules as `RetryFuture`.
+    fn into_stream<T: Sleep>(self, sleep: T) -> Ticks<T, Self> {
+        Ticks::new(sleep, self)
+    }
+}
+
+impl<S: Iterator<Item=Duration>> StrategyExt for S {}
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy driven by the fibonacci series.
+///
+/// Each retry uses a delay which is the sum of the two previous delays.
+#[derive(Clone, Debug)]
+pub struct FibonacciBackoff {
+    base: Duration,
+    current: Duration,
+    next: Duration
+}
+
+impl FibonacciBackoff {
+    /// Constructs a new fibonacci back-off strategy,
+    /// given a base duration in milliseconds.
+    ///
+    /// The first two delays are equal to the base, and every
+    /// following delay is the sum of the two preceding it.
+    pub fn from_millis(base: u64) -> FibonacciBackoff {
+        FibonacciBackoff::from_duration(Duration::from_millis(base))
+    }
+
+    /// Constructs a new fibonacci back-off strategy,
+    /// given a base duration.
+    ///
+    /// Sub-millisecond precision of the base is preserved.
+    pub fn from_duration(base: Duration) -> FibonacciBackoff {
+        FibonacciBackoff{base, current: base, next: base}
+    }
+}
+
+impl Iterator for FibonacciBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.current;
+        let next_next = self.current.checked_add(self.next).unwrap_or(Duration::MAX);
+
+        self.current = self.next;
+        self.next = next_next;
+
+        Some(duration)
+    }
+}
+
+impl fmt::Display for FibonacciBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "fibonacci(base={:?})", self.base)
+    }
+}
+
+impl ResettableStrategy for FibonacciBackoff {
+    fn reset(&mut self) {
+        self.current = self.base;
+        self.next = self.base;
+    }
+}
+
+#[test]
+fn returns_the_fibonacci_series_starting_at_10() {
+    let mut s = FibonacciBackoff::from_millis(10);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+    assert_eq!(s.next(), Some(Duration::from_millis(30)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(80)));
+}
+
+#[test]
+fn preserves_sub_millisecond_precision() {
+    let mut s = FibonacciBackoff::from_duration(Duration::from_micros(1500));
+
+    assert_eq!(s.next(), Some(Duration::from_micros(1500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(1500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(3000)));
+}
+
+#[test]
+fn saturates_at_maximum_value() {
+    let mut s = FibonacciBackoff::from_duration(Duration::MAX);
+
+    assert_eq!(s.next(), Some(Duration::MAX));
+    assert_eq!(s.next(), Some(Duration::MAX));
+    assert_eq!(s.next(), Some(Duration::MAX));
+}
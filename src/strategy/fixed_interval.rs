@@ -1,8 +1,11 @@
+use std::fmt;
 use std::time::Duration;
 use std::iter::Iterator;
 
+use super::ResettableStrategy;
+
 /// A retry strategy driven by a fixed interval.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct FixedInterval {
     duration: Duration
 }
@@ -10,7 +13,7 @@ pub struct FixedInterval {
 impl FixedInterval {
     /// Constructs a new fixed interval strategy.
     pub fn new(duration: Duration) -> FixedInterval {
-        FixedInterval{duration: duration}
+        FixedInterval{duration}
     }
 }
 
@@ -22,6 +25,16 @@ impl Iterator for FixedInterval {
     }
 }
 
+impl fmt::Display for FixedInterval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "fixed(interval={:?})", self.duration)
+    }
+}
+
+impl ResettableStrategy for FixedInterval {
+    fn reset(&mut self) {}
+}
+
 #[test]
 fn returns_some_fixed() {
     let mut s = FixedInterval::new(Duration::from_millis(123));
@@ -30,3 +43,8 @@ fn returns_some_fixed() {
     assert_eq!(s.next(), Some(Duration::from_millis(123)));
     assert_eq!(s.next(), Some(Duration::from_millis(123)));
 }
+
+#[test]
+fn displays_its_parameters() {
+    assert_eq!(FixedInterval::new(Duration::from_millis(123)).to_string(), "fixed(interval=123ms)");
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+use std::iter::Iterator;
+
+use backoff::backoff::Backoff;
+
+use super::ResettableStrategy;
+
+/// Adapts a strategy of the [`backoff`](https://crates.io/crates/backoff) crate,
+/// such as `backoff::ExponentialBackoff`, for use with `RetryFuture`.
+///
+/// This requires the `backoff` feature.
+pub fn from_backoff<B: Backoff>(backoff: B) -> FromBackoff<B> {
+    FromBackoff{backoff}
+}
+
+/// A retry strategy driven by a strategy of the `backoff` crate.
+///
+/// This `struct` is created by the [`from_backoff`](fn.from_backoff.html) function.
+#[derive(Clone, Debug)]
+pub struct FromBackoff<B> {
+    backoff: B
+}
+
+impl<B> FromBackoff<B> {
+    /// Unwraps the adapter, returning the underlying strategy.
+    pub fn into_inner(self) -> B {
+        self.backoff
+    }
+}
+
+impl<B: Backoff> Iterator for FromBackoff<B> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.backoff.next_backoff()
+    }
+}
+
+impl<B: Backoff> ResettableStrategy for FromBackoff<B> {
+    fn reset(&mut self) {
+        self.backoff.reset();
+    }
+}
+
+#[test]
+fn yields_the_delays_of_the_backoff_strategy() {
+    use backoff::ExponentialBackoffBuilder;
+    let backoff = ExponentialBackoffBuilder::new()
+        .with_initial_interval(Duration::from_millis(10))
+        .with_randomization_factor(0.0)
+        .with_multiplier(2.0)
+        .with_max_elapsed_time(None)
+        .build();
+    let mut s = from_backoff(backoff);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+
+    s.reset();
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+}
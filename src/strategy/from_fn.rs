@@ -0,0 +1,60 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy driven by a closure.
+///
+/// This `struct` is created by the [`from_fn`](fn.from_fn.html) function.
+#[derive(Clone)]
+pub struct FromFn<F> {
+    f: F,
+    attempt: usize
+}
+
+impl<F> fmt::Debug for FromFn<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("FromFn").field("attempt", &self.attempt).finish_non_exhaustive()
+    }
+}
+
+/// Constructs a new retry strategy from a closure.
+///
+/// The closure is called with the number of attempts made so far,
+/// starting at `1` for the delay after the first failed attempt.
+/// Returning `None` stops retrying.
+pub fn from_fn<F: FnMut(usize) -> Option<Duration>>(f: F) -> FromFn<F> {
+    FromFn{f, attempt: 0}
+}
+
+impl<F: FnMut(usize) -> Option<Duration>> Iterator for FromFn<F> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.attempt += 1;
+        (self.f)(self.attempt)
+    }
+}
+
+impl<F> ResettableStrategy for FromFn<F> {
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[test]
+fn returns_the_closure_results() {
+    let mut s = from_fn(|attempt| {
+        if attempt <= 3 {
+            Some(Duration::from_millis(attempt as u64 * 100))
+        } else {
+            None
+        }
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(200)));
+    assert_eq!(s.next(), Some(Duration::from_millis(300)));
+    assert_eq!(s.next(), None);
+}
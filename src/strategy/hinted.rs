@@ -0,0 +1,84 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// An error which can suggest how long to wait before retrying.
+///
+/// This is typically implemented by errors carrying a server-provided
+/// hint, such as the `Retry-After` header of an HTTP 429 response.
+pub trait BackoffHint {
+    /// Returns the suggested delay before the next attempt, if any.
+    fn retry_after(&self) -> Option<Duration>;
+}
+
+/// A retry strategy adapter which lets errors override the delays of another strategy.
+///
+/// This `struct` is created by the [`hinted`](fn.hinted.html) function.
+#[derive(Clone, Debug)]
+pub struct Hinted<S> {
+    inner: S
+}
+
+/// Wraps a strategy so that the [`BackoffHint`](trait.BackoffHint.html) of an error,
+/// when present, takes precedence over the delay the strategy would have used.
+///
+/// The wrapped strategy still decides when to stop retrying.
+pub fn hinted<T: IntoStrategy>(strategy: T) -> Hinted<T::Strategy> {
+    Hinted{inner: strategy.into_strategy()}
+}
+
+impl<E: BackoffHint, S: BackoffStrategy<E>> BackoffStrategy<E> for Hinted<S> {
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration> {
+        let duration = self.inner.delay(attempt, error)?;
+        Some(error.retry_after().unwrap_or(duration))
+    }
+
+    fn succeeded(&mut self) {
+        self.inner.succeeded()
+    }
+
+    fn attempts_exhausted(&self) -> bool {
+        self.inner.attempts_exhausted()
+    }
+}
+
+impl<S> IntoStrategy for Hinted<S> {
+    type Strategy = Hinted<S>;
+
+    fn into_strategy(self) -> Hinted<S> {
+        self
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Hinted<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "hinted({})", self.inner)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Hinted<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn prefers_the_hint_of_an_error() {
+    use super::FixedInterval;
+
+    struct Hint(Option<Duration>);
+
+    impl BackoffHint for Hint {
+        fn retry_after(&self) -> Option<Duration> {
+            self.0
+        }
+    }
+
+    let mut s = hinted(FixedInterval::new(Duration::from_secs(1)).take(3));
+
+    assert_eq!(s.delay(1, &Hint(Some(Duration::from_secs(30)))), Some(Duration::from_secs(30)));
+    assert_eq!(s.delay(2, &Hint(None)), Some(Duration::from_secs(1)));
+    assert_eq!(s.delay(3, &Hint(Some(Duration::from_secs(30)))), Some(Duration::from_secs(30)));
+    assert_eq!(s.delay(4, &Hint(Some(Duration::from_secs(30)))), None);
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use super::{saturating_mul, DefaultRng, JitterRng};
+
+/// Randomizes a duration by scaling it with a factor in the closed interval `[0, 1]`.
+pub fn jitter(duration: Duration) -> Duration {
+    jitter_rng(duration, &mut DefaultRng)
+}
+
+pub(crate) fn jitter_rng<R: JitterRng>(duration: Duration, rng: &mut R) -> Duration {
+    let jitter = rng.next_unit();
+    let secs = ((duration.as_secs() as f64) * jitter).ceil() as u64;
+    let nanos = ((duration.subsec_nanos() as f64) * jitter).ceil() as u32;
+    Duration::new(secs, nanos)
+}
+
+pub(crate) fn proportional_jitter_rng<R: JitterRng>(duration: Duration, spread: f64, rng: &mut R) -> Duration {
+    let jitter = rng.next_unit();
+    saturating_mul(duration, 1.0 + spread * (2.0 * jitter - 1.0))
+}
+
+/// Randomizes a duration uniformly between zero and the duration itself.
+///
+/// This is the "full jitter" profile, which spreads out contending clients the most.
+pub fn full_jitter(duration: Duration) -> Duration {
+    let jitter = DefaultRng.next_unit();
+    duration.mul_f64(jitter)
+}
+
+/// Randomizes a duration by keeping half of it fixed and randomizing the other half.
+///
+/// This is the "equal jitter" profile, which never sleeps for less than half the delay.
+pub fn equal_jitter(duration: Duration) -> Duration {
+    let jitter = DefaultRng.next_unit();
+    let half = duration / 2;
+    half + half.mul_f64(jitter)
+}
+
+#[test]
+fn full_jitter_stays_within_delay() {
+    let duration = Duration::from_millis(100);
+
+    for _ in 0..100 {
+        assert!(full_jitter(duration) <= duration);
+    }
+}
+
+#[test]
+fn equal_jitter_stays_within_upper_half_of_delay() {
+    let duration = Duration::from_millis(100);
+
+    for _ in 0..100 {
+        let jittered = equal_jitter(duration);
+        assert!(jittered >= Duration::from_millis(50));
+        assert!(jittered <= duration);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+
+use super::{DefaultRng, JitterRng, ResettableStrategy};
+use super::jitter::{jitter_rng, proportional_jitter_rng};
+
+/// A retry strategy adapter which randomizes the delays of another strategy.
+///
+/// This `struct` is created by the [`jittered`](trait.StrategyExt.html#method.jittered),
+/// [`jitter_with`](trait.StrategyExt.html#method.jitter_with) and
+/// [`jitter_pct`](trait.StrategyExt.html#method.jitter_pct) methods
+/// on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Jittered<S, R = DefaultRng> {
+    inner: S,
+    rng: R,
+    profile: Profile
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Profile {
+    Scale,
+    Proportional(f64)
+}
+
+impl<S, R> Jittered<S, R> {
+    pub(crate) fn new(inner: S, rng: R) -> Jittered<S, R> {
+        Jittered{inner, rng, profile: Profile::Scale}
+    }
+
+    pub(crate) fn proportional(inner: S, rng: R, spread: f64) -> Jittered<S, R> {
+        Jittered{inner, rng, profile: Profile::Proportional(spread)}
+    }
+}
+
+impl<S: Iterator<Item=Duration>, R: JitterRng> Iterator for Jittered<S, R> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let rng = &mut self.rng;
+        let profile = self.profile;
+        self.inner.next().map(|duration| match profile {
+            Profile::Scale => jitter_rng(duration, rng),
+            Profile::Proportional(spread) => proportional_jitter_rng(duration, spread, rng)
+        })
+    }
+}
+
+impl<S: fmt::Display, R> fmt::Display for Jittered<S, R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.profile {
+            Profile::Scale => write!(formatter, "jittered({})", self.inner),
+            Profile::Proportional(spread) => write!(formatter, "jittered({}, spread={})", self.inner, spread)
+        }
+    }
+}
+
+impl<S: ResettableStrategy, R> ResettableStrategy for Jittered<S, R> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn stays_within_delay() {
+    use super::{FixedInterval, StrategyExt};
+    let duration = Duration::from_millis(100);
+    let mut s = FixedInterval::new(duration).jittered();
+
+    for _ in 0..100 {
+        assert!(s.next().unwrap() <= duration);
+    }
+}
+
+#[test]
+fn is_deterministic_for_a_seeded_rng() {
+    use super::{ExponentialBackoff, StrategyExt, Xorshift};
+    let a = ExponentialBackoff::from_millis(10).jitter_with(Xorshift::new(1234));
+    let b = ExponentialBackoff::from_millis(10).jitter_with(Xorshift::new(1234));
+
+    assert_eq!(a.take(10).collect::<Vec<_>>(), b.take(10).collect::<Vec<_>>());
+}
+
+#[test]
+fn stays_within_proportional_spread() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(100)).jitter_pct(0.25);
+
+    for _ in 0..100 {
+        let duration = s.next().unwrap();
+        assert!(duration >= Duration::from_millis(75));
+        assert!(duration <= Duration::from_millis(125));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy driven by linear back-off.
+///
+/// The delay grows by a fixed increment with every attempt.
+#[derive(Clone, Debug)]
+pub struct LinearBackoff {
+    initial: Duration,
+    current: Duration,
+    increment: Duration
+}
+
+impl LinearBackoff {
+    /// Constructs a new linear back-off strategy,
+    /// given an initial duration and the increment to add after each attempt.
+    pub fn new(initial: Duration, increment: Duration) -> LinearBackoff {
+        LinearBackoff{initial, current: initial, increment}
+    }
+}
+
+impl Iterator for LinearBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.current;
+
+        self.current = self.current.checked_add(self.increment).unwrap_or(Duration::MAX);
+
+        Some(duration)
+    }
+}
+
+impl fmt::Display for LinearBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "linear(initial={:?}, increment={:?})", self.initial, self.increment)
+    }
+}
+
+impl ResettableStrategy for LinearBackoff {
+    fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+#[test]
+fn returns_some_linear() {
+    let mut s = LinearBackoff::new(Duration::from_millis(100), Duration::from_millis(100));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(200)));
+    assert_eq!(s.next(), Some(Duration::from_millis(300)));
+}
+
+#[test]
+fn returns_some_linear_with_distinct_initial() {
+    let mut s = LinearBackoff::new(Duration::from_millis(50), Duration::from_millis(20));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(70)));
+    assert_eq!(s.next(), Some(Duration::from_millis(90)));
+}
+
+#[test]
+fn saturates_at_maximum_value() {
+    let mut s = LinearBackoff::new(Duration::MAX, Duration::from_millis(1));
+
+    assert_eq!(s.next(), Some(Duration::MAX));
+    assert_eq!(s.next(), Some(Duration::MAX));
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// A retry strategy adapter which limits the total number of attempts.
+///
+/// Once the attempts are used up, it reports so via `BackoffStrategy::attempts_exhausted`,
+/// so that the resulting `RetryError::RetriesExhausted` can be told apart from the wrapped
+/// strategy giving up on its own.
+///
+/// Reporting so requires implementing `BackoffStrategy` directly, which rules out being an
+/// `Iterator` as well. Iterator adapters such as `jittered` or `capped` therefore need to be
+/// applied to the wrapped strategy, before `max_attempts`.
+///
+/// This `struct` is created by the [`max_attempts`](trait.StrategyExt.html#method.max_attempts)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct MaxAttempts<S> {
+    inner: S,
+    attempts: usize,
+    remaining: usize,
+    exhausted: bool
+}
+
+impl<S> MaxAttempts<S> {
+    pub(crate) fn new(inner: S, attempts: usize) -> MaxAttempts<S> {
+        MaxAttempts{inner, attempts, remaining: attempts.saturating_sub(1), exhausted: false}
+    }
+}
+
+impl<E, S: BackoffStrategy<E>> BackoffStrategy<E> for MaxAttempts<S> {
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration> {
+        if self.remaining == 0 {
+            self.exhausted = true;
+            return None;
+        }
+
+        let duration = self.inner.delay(attempt, error)?;
+        self.remaining -= 1;
+        Some(duration)
+    }
+
+    fn succeeded(&mut self) {
+        self.inner.succeeded()
+    }
+
+    fn attempts_exhausted(&self) -> bool {
+        self.exhausted || self.inner.attempts_exhausted()
+    }
+}
+
+impl<S> IntoStrategy for MaxAttempts<S> {
+    type Strategy = MaxAttempts<S>;
+
+    fn into_strategy(self) -> MaxAttempts<S> {
+        self
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for MaxAttempts<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "max_attempts({}, attempts={})", self.inner, self.attempts)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for MaxAttempts<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.remaining = self.attempts.saturating_sub(1);
+        self.exhausted = false;
+    }
+}
+
+#[test]
+fn yields_one_delay_less_than_attempts() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(10)).max_attempts(3);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 3, &()), None);
+    assert!(BackoffStrategy::<()>::attempts_exhausted(&s));
+}
+
+#[test]
+fn never_retries_for_a_single_attempt() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(10)).max_attempts(1);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), None);
+}
+
+#[test]
+fn does_not_report_exhausted_attempts_for_the_inner_strategy_giving_up() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(10)).take(1).max_attempts(3);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), None);
+    assert!(!BackoffStrategy::<()>::attempts_exhausted(&s));
+}
+
+#[test]
+fn restores_attempts_on_reset() {
+    use super::{LinearBackoff, StrategyExt};
+    let mut s = LinearBackoff::new(Duration::from_millis(10), Duration::from_millis(10)).max_attempts(2);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), None);
+
+    s.reset();
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), None);
+}
+
+#[test]
+fn composes_after_iterator_adapters() {
+    use super::{ExponentialBackoff, StrategyExt};
+    let mut s = ExponentialBackoff::from_millis(100).capped(Duration::from_millis(500)).max_attempts(3);
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(100)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(500)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 3, &()), None);
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy adapter which stops once the cumulative delay would exceed a budget.
+///
+/// This `struct` is created by the [`max_total_delay`](trait.StrategyExt.html#method.max_total_delay)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct MaxTotalDelay<S> {
+    inner: S,
+    budget: Duration,
+    total: Duration
+}
+
+impl<S> MaxTotalDelay<S> {
+    pub(crate) fn new(inner: S, budget: Duration) -> MaxTotalDelay<S> {
+        MaxTotalDelay{inner, budget, total: Duration::ZERO}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for MaxTotalDelay<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.inner.next()?;
+        let total = self.total.checked_add(duration)?;
+
+        if total > self.budget {
+            return None;
+        }
+
+        self.total = total;
+        Some(duration)
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for MaxTotalDelay<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "max_total_delay({}, budget={:?})", self.inner, self.budget)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for MaxTotalDelay<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.total = Duration::ZERO;
+    }
+}
+
+#[test]
+fn stops_once_budget_is_exceeded() {
+    use super::{ExponentialBackoff, StrategyExt};
+    let mut s = ExponentialBackoff::from_millis(10).max_total_delay(Duration::from_millis(200));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn allows_delays_up_to_the_budget() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(50)).max_total_delay(Duration::from_millis(100));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), None);
+}
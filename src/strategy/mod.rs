@@ -1,15 +1,121 @@
+//! Assorted retry strategies including fixed interval, linear, exponential and fibonacci back-off.
+//!
+//! A strategy is any `Iterator<Item=Duration>`, so the adapters from the standard library
+//! can be used to combine them. Retry-specific adapters, which stay `Clone` and nameable,
+//! are provided by the [`StrategyExt`](trait.StrategyExt.html) trait.
+//!
+//! # Tiered strategies
+//!
+//! Use [`then`](trait.StrategyExt.html#method.then) to switch to a different strategy once
+//! another one is exhausted, e.g. to retry three times quickly before falling back to
+//! exponential back-off:
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use tokio_retry::strategy::{BackoffStrategy, FixedInterval, ExponentialBackoff, StrategyExt};
+//!
+//! let mut strategy = FixedInterval::new(Duration::from_millis(10))
+//!     .take(3)
+//!     .then(ExponentialBackoff::from_millis(100));
+//!
+//! for attempt in 1..4 {
+//!     assert_eq!(BackoffStrategy::<()>::delay(&mut strategy, attempt, &()), Some(Duration::from_millis(10)));
+//! }
+//! assert_eq!(BackoffStrategy::<()>::delay(&mut strategy, 4, &()), Some(Duration::from_millis(100)));
+//! assert_eq!(BackoffStrategy::<()>::delay(&mut strategy, 5, &()), Some(Duration::from_millis(10000)));
+//! ```
+//!
+//! The resulting `Then` stays `Clone` as long as both strategies are, so it can be used
+//! with `RetryService` as well, and starts over with the first strategy when reset.
+
 use std::time::Duration;
-use rand::{random, Closed01};
 
+mod backoff_strategy;
 mod fixed_interval;
 mod exponential_backoff;
+mod fibonacci_backoff;
+mod linear_backoff;
+mod decorrelated_jitter;
+mod random_interval;
+mod no_delay;
+mod explicit_schedule;
+mod from_fn;
+#[cfg(feature = "backoff")]
+mod from_backoff;
+mod polynomial_backoff;
+mod decaying_interval;
+mod truncated_binary_backoff;
+mod aimd_backoff;
+mod aligned_interval;
+mod coordinated_backoff;
+mod hinted;
+mod budgeted;
+mod then;
+mod routed;
+mod spec;
+mod ext;
+mod jittered;
+mod scaled;
+mod capped;
+mod clamp_min;
+mod max_total_delay;
+mod max_attempts;
+mod probing;
+mod shared_backoff;
+mod until_deadline;
+mod windowed;
+mod jitter;
+mod rng;
 
+pub use self::backoff_strategy::{BackoffStrategy, ResettableStrategy, IntoStrategy};
 pub use self::fixed_interval::FixedInterval;
 pub use self::exponential_backoff::ExponentialBackoff;
+pub use self::fibonacci_backoff::FibonacciBackoff;
+pub use self::linear_backoff::LinearBackoff;
+pub use self::decorrelated_jitter::DecorrelatedJitter;
+pub use self::random_interval::RandomInterval;
+pub use self::no_delay::NoDelay;
+pub use self::explicit_schedule::ExplicitSchedule;
+pub use self::from_fn::{from_fn, FromFn};
+#[cfg(feature = "backoff")]
+pub use self::from_backoff::{from_backoff, FromBackoff};
+pub use self::polynomial_backoff::PolynomialBackoff;
+pub use self::decaying_interval::DecayingInterval;
+pub use self::truncated_binary_backoff::TruncatedBinaryBackoff;
+pub use self::aimd_backoff::AimdBackoff;
+pub use self::aligned_interval::AlignedInterval;
+pub use self::coordinated_backoff::CoordinatedBackoff;
+pub use self::hinted::{hinted, BackoffHint, Hinted};
+pub use self::budgeted::{budgeted, Budgeted, RetryBudget};
+pub use self::routed::{routed, Routed};
+pub use self::spec::{StrategySpec, ParseStrategyError};
+pub use self::ext::StrategyExt;
+pub use self::jittered::Jittered;
+pub use self::scaled::Scaled;
+pub use self::capped::Capped;
+pub use self::clamp_min::ClampMin;
+pub use self::max_total_delay::MaxTotalDelay;
+pub use self::max_attempts::MaxAttempts;
+pub use self::then::Then;
+pub use self::probing::Probing;
+pub use self::shared_backoff::SharedBackoff;
+pub use self::until_deadline::UntilDeadline;
+pub use self::windowed::{TimeWindow, Windowed};
+pub use self::jitter::{jitter, full_jitter, equal_jitter};
+pub use self::rng::{JitterRng, DefaultRng, Xorshift};
+
+/// Multiplies a duration by a factor, saturating at `Duration::MAX`.
+pub(crate) fn saturating_mul(duration: Duration, factor: f64) -> Duration {
+    let nanos = (duration.as_nanos() as f64 * factor).round();
+
+    if nanos.is_nan() || nanos <= 0.0 {
+        return Duration::from_secs(0);
+    }
+
+    if nanos >= Duration::MAX.as_nanos() as f64 {
+        return Duration::MAX;
+    }
 
-pub fn jitter(duration: Duration) -> Duration {
-    let Closed01(jitter) = random::<Closed01<f64>>();
-    let secs = ((duration.as_secs() as f64) * jitter).ceil() as u64;
-    let nanos = ((duration.subsec_nanos() as f64) * jitter).ceil() as u32;
-    return Duration::new(secs, nanos);
+    let nanos = nanos as u128;
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
 }
@@ -0,0 +1,38 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy that retries immediately.
+///
+/// `RetryFuture` re-runs the action right away for zero delays,
+/// without going through the timer.
+#[derive(Clone, Debug)]
+pub struct NoDelay;
+
+impl Iterator for NoDelay {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        Some(Duration::ZERO)
+    }
+}
+
+impl fmt::Display for NoDelay {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "no_delay")
+    }
+}
+
+impl ResettableStrategy for NoDelay {
+    fn reset(&mut self) {}
+}
+
+#[test]
+fn returns_some_zero() {
+    let mut s = NoDelay;
+
+    assert_eq!(s.next(), Some(Duration::ZERO));
+    assert_eq!(s.next(), Some(Duration::ZERO));
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{saturating_mul, ResettableStrategy};
+
+/// A retry strategy driven by polynomial back-off.
+///
+/// The delay after the `n`-th attempt is the base multiplied by `n` to the power of the exponent.
+#[derive(Clone, Debug)]
+pub struct PolynomialBackoff {
+    base: Duration,
+    exponent: f64,
+    attempt: u64
+}
+
+impl PolynomialBackoff {
+    /// Constructs a new polynomial back-off strategy,
+    /// given a base duration and an exponent.
+    ///
+    /// An exponent of `1` is equivalent to linear back-off,
+    /// `2` yields quadratic growth, and so on.
+    pub fn new(base: Duration, exponent: f64) -> PolynomialBackoff {
+        PolynomialBackoff{base, exponent, attempt: 0}
+    }
+}
+
+impl Iterator for PolynomialBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.attempt = self.attempt.saturating_add(1);
+        Some(saturating_mul(self.base, (self.attempt as f64).powf(self.exponent)))
+    }
+}
+
+impl fmt::Display for PolynomialBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "polynomial(base={:?}, exponent={})", self.base, self.exponent)
+    }
+}
+
+impl ResettableStrategy for PolynomialBackoff {
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[test]
+fn returns_some_quadratic() {
+    let mut s = PolynomialBackoff::new(Duration::from_millis(10), 2.0);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+    assert_eq!(s.next(), Some(Duration::from_millis(90)));
+    assert_eq!(s.next(), Some(Duration::from_millis(160)));
+}
+
+#[test]
+fn saturates_at_maximum_value() {
+    let mut s = PolynomialBackoff::new(Duration::MAX, 3.0);
+
+    assert_eq!(s.next(), Some(Duration::MAX));
+    assert_eq!(s.next(), Some(Duration::MAX));
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy adapter which periodically replaces a delay with a short probe.
+///
+/// This `struct` is created by the [`with_probe`](trait.StrategyExt.html#method.with_probe)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Probing<S> {
+    inner: S,
+    probe: Duration,
+    every: usize,
+    count: usize
+}
+
+impl<S> Probing<S> {
+    pub(crate) fn new(inner: S, probe: Duration, every: usize) -> Probing<S> {
+        Probing{inner, probe, every, count: 0}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for Probing<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.inner.next()?;
+
+        self.count += 1;
+        if self.count == self.every {
+            self.count = 0;
+            return Some(self.probe);
+        }
+
+        Some(duration)
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Probing<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "probing({}, probe={:?}, every={})", self.inner, self.probe, self.every)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Probing<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.count = 0;
+    }
+}
+
+#[test]
+fn probes_on_every_nth_delay() {
+    use super::{ExponentialBackoff, StrategyExt};
+    let mut s = ExponentialBackoff::from_millis(10)
+        .max_delay(Duration::from_secs(60))
+        .with_probe(Duration::from_millis(1), 3);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(1)));
+    assert_eq!(s.next(), Some(Duration::from_secs(10)));
+    assert_eq!(s.next(), Some(Duration::from_secs(60)));
+    assert_eq!(s.next(), Some(Duration::from_millis(1)));
+    assert_eq!(s.next(), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn stops_with_the_inner_strategy() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_secs(1)).take(1).with_probe(Duration::from_millis(1), 1);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(1)));
+    assert_eq!(s.next(), None);
+}
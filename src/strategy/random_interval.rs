@@ -0,0 +1,65 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{DefaultRng, JitterRng, ResettableStrategy};
+
+/// A retry strategy driven by uniformly distributed random intervals.
+#[derive(Clone, Debug)]
+pub struct RandomInterval {
+    min: Duration,
+    max: Duration
+}
+
+impl RandomInterval {
+    /// Constructs a new random interval strategy,
+    /// yielding durations between `min` and `max` inclusively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max`.
+    pub fn new(min: Duration, max: Duration) -> RandomInterval {
+        assert!(min <= max, "RandomInterval: min must not exceed max");
+        RandomInterval{min, max}
+    }
+}
+
+impl Iterator for RandomInterval {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let jitter = DefaultRng.next_unit();
+        Some(self.min + (self.max - self.min).mul_f64(jitter))
+    }
+}
+
+impl fmt::Display for RandomInterval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "random(min={:?}, max={:?})", self.min, self.max)
+    }
+}
+
+impl ResettableStrategy for RandomInterval {
+    fn reset(&mut self) {}
+}
+
+#[test]
+fn returns_some_within_range() {
+    let min = Duration::from_millis(100);
+    let max = Duration::from_millis(200);
+    let mut s = RandomInterval::new(min, max);
+
+    for _ in 0..100 {
+        let duration = s.next().unwrap();
+        assert!(duration >= min);
+        assert!(duration <= max);
+    }
+}
+
+#[test]
+fn returns_some_fixed_for_empty_range() {
+    let mut s = RandomInterval::new(Duration::from_millis(100), Duration::from_millis(100));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+}
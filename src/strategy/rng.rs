@@ -0,0 +1,117 @@
+#[cfg(feature = "rand")]
+use rand::{thread_rng, Closed01, Rng};
+#[cfg(not(feature = "rand"))]
+use std::cell::Cell;
+#[cfg(not(feature = "rand"))]
+use std::collections::hash_map::RandomState;
+#[cfg(not(feature = "rand"))]
+use std::hash::{BuildHasher, Hasher};
+
+/// A source of randomness for jittered strategies.
+///
+/// With the `rand` feature enabled, this is implemented for every `rand::Rng`.
+pub trait JitterRng {
+    /// Returns a uniformly distributed number in the closed interval `[0, 1]`.
+    fn next_unit(&mut self) -> f64;
+}
+
+#[cfg(feature = "rand")]
+impl<R: Rng> JitterRng for R {
+    fn next_unit(&mut self) -> f64 {
+        let Closed01(unit) = self.gen::<Closed01<f64>>();
+        unit
+    }
+}
+
+/// The random number generator used for jitter unless another one is supplied.
+///
+/// It draws from a thread-local generator on every call, so unlike
+/// `rand::ThreadRng` it can be stored in a strategy which is sent across threads.
+/// Without the `rand` feature, the thread-local generator is an [`Xorshift`](struct.Xorshift.html)
+/// seeded from the standard library's per-thread hash keys.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRng;
+
+#[cfg(feature = "rand")]
+impl Rng for DefaultRng {
+    fn next_u32(&mut self) -> u32 {
+        thread_rng().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        thread_rng().next_u64()
+    }
+}
+
+#[cfg(not(feature = "rand"))]
+thread_local!(static DEFAULT_RNG: Cell<Xorshift> = Cell::new(Xorshift::new(RandomState::new().build_hasher().finish())));
+
+#[cfg(not(feature = "rand"))]
+impl JitterRng for DefaultRng {
+    fn next_unit(&mut self) -> f64 {
+        DEFAULT_RNG.with(|cell| {
+            let mut rng = cell.get();
+            let unit = rng.next_unit();
+            cell.set(rng);
+            unit
+        })
+    }
+}
+
+/// A small, dependency-free xorshift* pseudo-random number generator.
+///
+/// It is not suitable for cryptographic purposes, but plenty for spreading out retries.
+/// Seeding it explicitly yields a deterministic schedule.
+#[derive(Clone, Copy, Debug)]
+pub struct Xorshift {
+    state: u64
+}
+
+impl Xorshift {
+    /// Constructs a new generator from the given seed.
+    pub fn new(seed: u64) -> Xorshift {
+        // The all-zero state is a fixed point of xorshift.
+        Xorshift{state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed }}
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl JitterRng for Xorshift {
+    fn next_unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / ((1u64 << 53) - 1) as f64
+    }
+}
+
+#[test]
+fn xorshift_stays_within_unit_interval() {
+    let mut rng = Xorshift::new(42);
+
+    for _ in 0..1000 {
+        let unit = rng.next_unit();
+        assert!((0.0..=1.0).contains(&unit));
+    }
+}
+
+#[test]
+fn xorshift_is_deterministic() {
+    let mut a = Xorshift::new(7);
+    let mut b = Xorshift::new(7);
+
+    for _ in 0..10 {
+        assert_eq!(a.next_unit(), b.next_unit());
+    }
+}
+
+#[test]
+fn default_rng_stays_within_unit_interval() {
+    for _ in 0..1000 {
+        let unit = DefaultRng.next_unit();
+        assert!((0.0..=1.0).contains(&unit));
+    }
+}
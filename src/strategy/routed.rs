@@ -0,0 +1,138 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy};
+
+type BoxedStrategy<E> = Box<dyn BackoffStrategy<E> + Send>;
+
+/// Creates a strategy which routes every error to the strategy registered for its class.
+///
+/// Each class advances its own schedule, so that e.g. throttling errors can
+/// back off exponentially while connection resets are retried at a short
+/// fixed interval within the same retry. Errors of unregistered classes are
+/// routed to the fallback strategy, if any, and not retried otherwise. Should a route give up
+/// because it ran out of attempts, e.g. via `max_attempts`, the routed strategy reports so.
+pub fn routed<E, K, F>(classify: F) -> Routed<E, K, F> where K: PartialEq, F: FnMut(&E) -> K {
+    Routed{classify, routes: Vec::new(), fallback: None, exhausted: false}
+}
+
+/// A retry strategy choosing between several strategies depending on the error.
+///
+/// This `struct` is created by the [`routed`](fn.routed.html) function.
+pub struct Routed<E, K, F> {
+    classify: F,
+    routes: Vec<(K, BoxedStrategy<E>)>,
+    fallback: Option<BoxedStrategy<E>>,
+    exhausted: bool
+}
+
+impl<E, K: fmt::Debug, F> fmt::Debug for Routed<E, K, F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("Routed")
+            .field("classes", &self.routes.iter().map(|(class, _)| class).collect::<Vec<_>>())
+            .field("fallback", &self.fallback.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E, K, F> Routed<E, K, F> where K: PartialEq, F: FnMut(&E) -> K {
+    /// Registers the strategy for errors of the given class.
+    pub fn route<T>(mut self, class: K, strategy: T) -> Routed<E, K, F> where T: IntoStrategy, T::Strategy: BackoffStrategy<E> + Send + 'static {
+        self.routes.push((class, Box::new(strategy.into_strategy())));
+        self
+    }
+
+    /// Registers the strategy for errors of all other classes.
+    pub fn otherwise<T>(mut self, strategy: T) -> Routed<E, K, F> where T: IntoStrategy, T::Strategy: BackoffStrategy<E> + Send + 'static {
+        self.fallback = Some(Box::new(strategy.into_strategy()));
+        self
+    }
+}
+
+impl<E, K, F> BackoffStrategy<E> for Routed<E, K, F> where K: PartialEq, F: FnMut(&E) -> K {
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration> {
+        let class = (self.classify)(error);
+
+        let strategy = match self.routes.iter_mut().find(|route| route.0 == class) {
+            Some(route) => &mut route.1,
+            None => match self.fallback {
+                Some(ref mut fallback) => fallback,
+                None => {
+                    self.exhausted = false;
+                    return None;
+                }
+            }
+        };
+        let duration = strategy.delay(attempt, error);
+        // Only the route which gave up can tell whether it ran out of attempts.
+        self.exhausted = duration.is_none() && strategy.attempts_exhausted();
+        duration
+    }
+
+    fn succeeded(&mut self) {
+        for route in &mut self.routes {
+            route.1.succeeded();
+        }
+        if let Some(ref mut fallback) = self.fallback {
+            fallback.succeeded();
+        }
+    }
+
+    fn attempts_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+impl<E, K, F> IntoStrategy for Routed<E, K, F> {
+    type Strategy = Routed<E, K, F>;
+
+    fn into_strategy(self) -> Routed<E, K, F> {
+        self
+    }
+}
+
+#[test]
+fn advances_the_schedule_of_each_class() {
+    use super::{ExponentialBackoff, FixedInterval};
+
+    #[derive(PartialEq)]
+    enum Class { Throttled, Reset }
+
+    let mut s = routed(|err: &&str| if *err == "throttled" { Class::Throttled } else { Class::Reset })
+        .route(Class::Throttled, ExponentialBackoff::from_millis(10))
+        .route(Class::Reset, FixedInterval::new(Duration::from_millis(5)).take(1));
+
+    assert_eq!(s.delay(1, &"throttled"), Some(Duration::from_millis(10)));
+    assert_eq!(s.delay(2, &"reset"), Some(Duration::from_millis(5)));
+    assert_eq!(s.delay(3, &"throttled"), Some(Duration::from_millis(100)));
+    assert_eq!(s.delay(4, &"reset"), None);
+}
+
+#[test]
+fn falls_back_for_unregistered_classes() {
+    use super::FixedInterval;
+    let mut s = routed(|err: &u64| *err)
+        .route(1, FixedInterval::new(Duration::from_millis(1)));
+
+    assert_eq!(s.delay(1, &2), None);
+
+    let mut s = s.otherwise(FixedInterval::new(Duration::from_millis(2)));
+
+    assert_eq!(s.delay(2, &2), Some(Duration::from_millis(2)));
+}
+
+#[test]
+fn reports_exhausted_attempts_of_the_route_which_gave_up() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = routed(|err: &u64| *err)
+        .route(1, FixedInterval::new(Duration::from_millis(1)).max_attempts(2))
+        .route(2, FixedInterval::new(Duration::from_millis(2)).take(1));
+
+    assert_eq!(s.delay(1, &1), Some(Duration::from_millis(1)));
+    assert_eq!(s.delay(2, &1), None);
+    assert!(BackoffStrategy::<u64>::attempts_exhausted(&s));
+
+    assert_eq!(s.delay(3, &2), Some(Duration::from_millis(2)));
+    assert_eq!(s.delay(4, &2), None);
+    assert!(!BackoffStrategy::<u64>::attempts_exhausted(&s));
+}
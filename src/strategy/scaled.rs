@@ -0,0 +1,52 @@
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{saturating_mul, ResettableStrategy};
+
+/// A retry strategy adapter which multiplies the delays of another strategy by a factor.
+///
+/// This `struct` is created by the [`scaled`](trait.StrategyExt.html#method.scaled)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Scaled<S> {
+    inner: S,
+    factor: f64
+}
+
+impl<S> Scaled<S> {
+    pub(crate) fn new(inner: S, factor: f64) -> Scaled<S> {
+        Scaled{inner, factor}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for Scaled<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let factor = self.factor;
+        self.inner.next().map(|duration| saturating_mul(duration, factor))
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Scaled<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "scaled({}, factor={})", self.inner, self.factor)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Scaled<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn returns_some_scaled() {
+    use super::{ExponentialBackoff, StrategyExt};
+    let mut s = ExponentialBackoff::from_millis(10).scaled(0.5);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(5)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::iter::Iterator;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// A retry strategy which warm-starts from where the last successful invocation left off.
+///
+/// Clones share the level of the last delay that led to a success, so a
+/// `SharedBackoff` reused across separate `RetryFuture::spawn` calls against
+/// the same resource resumes the schedule at that delay instead of its start.
+/// Each success on the first attempt lowers the level by one step again.
+///
+/// Warm starts skip over the first delays of the wrapped strategy, so apply
+/// limits like `max_attempts` to the `SharedBackoff` rather than to the
+/// strategy it wraps.
+#[derive(Debug)]
+pub struct SharedBackoff<S> {
+    template: S,
+    current: Option<S>,
+    start: usize,
+    retries: usize,
+    level: Arc<Mutex<usize>>
+}
+
+impl<S: Clone> SharedBackoff<S> {
+    /// Wraps a strategy, starting at its first delay.
+    pub fn new<T: IntoStrategy<Strategy=S>>(strategy: T) -> SharedBackoff<S> {
+        SharedBackoff{template: strategy.into_strategy(), current: None, start: 0, retries: 0, level: Arc::new(Mutex::new(0))}
+    }
+
+    /// Returns the number of delays the next invocation will skip.
+    pub fn level(&self) -> usize {
+        *self.level.lock().unwrap()
+    }
+}
+
+impl<S: Clone> Clone for SharedBackoff<S> {
+    fn clone(&self) -> SharedBackoff<S> {
+        SharedBackoff{template: self.template.clone(), current: None, start: 0, retries: 0, level: self.level.clone()}
+    }
+}
+
+impl<E, S: Clone + Iterator<Item=Duration>> BackoffStrategy<E> for SharedBackoff<S> {
+    fn delay(&mut self, _: usize, _: &E) -> Option<Duration> {
+        if self.current.is_none() {
+            let mut strategy = self.template.clone();
+            self.start = *self.level.lock().unwrap();
+            for _ in 0..self.start {
+                strategy.next();
+            }
+            self.current = Some(strategy);
+        }
+
+        self.retries += 1;
+        self.current.as_mut().and_then(Iterator::next)
+    }
+
+    fn succeeded(&mut self) {
+        let mut level = self.level.lock().unwrap();
+
+        *level = match self.retries {
+            0 => level.saturating_sub(1),
+            retries => self.start + retries - 1
+        };
+
+        self.current = None;
+        self.retries = 0;
+    }
+}
+
+impl<S> IntoStrategy for SharedBackoff<S> {
+    type Strategy = SharedBackoff<S>;
+
+    fn into_strategy(self) -> SharedBackoff<S> {
+        self
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for SharedBackoff<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "shared({}, level={})", self.template, *self.level.lock().unwrap())
+    }
+}
+
+impl<S> ResettableStrategy for SharedBackoff<S> {
+    fn reset(&mut self) {
+        *self.level.lock().unwrap() = 0;
+        self.current = None;
+        self.retries = 0;
+    }
+}
+
+#[test]
+fn resumes_at_the_last_successful_delay() {
+    use super::ExponentialBackoff;
+    let shared = SharedBackoff::new(ExponentialBackoff::from_duration(Duration::from_millis(10)));
+
+    let mut first = shared.clone();
+    assert_eq!(first.delay(1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(first.delay(2, &()), Some(Duration::from_millis(20)));
+    assert_eq!(first.delay(3, &()), Some(Duration::from_millis(40)));
+    BackoffStrategy::<()>::succeeded(&mut first);
+
+    let mut second = shared.clone();
+    assert_eq!(second.delay(1, &()), Some(Duration::from_millis(40)));
+    assert_eq!(second.delay(2, &()), Some(Duration::from_millis(80)));
+}
+
+#[test]
+fn cools_down_on_immediate_success() {
+    use super::ExponentialBackoff;
+    let mut shared = SharedBackoff::new(ExponentialBackoff::from_duration(Duration::from_millis(10)));
+
+    shared.delay(1, &());
+    shared.delay(2, &());
+    BackoffStrategy::<()>::succeeded(&mut shared);
+    assert_eq!(shared.level(), 1);
+
+    BackoffStrategy::<()>::succeeded(&mut shared);
+    assert_eq!(shared.level(), 0);
+    assert_eq!(shared.delay(1, &()), Some(Duration::from_millis(10)));
+}
@@ -0,0 +1,229 @@
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+use std::iter::Iterator;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{jitter, ExponentialBackoff, FibonacciBackoff, FixedInterval, LinearBackoff, NoDelay, ResettableStrategy};
+
+/// A retry strategy parsed from a compact textual specification.
+///
+/// This allows tuning retry behaviour through configuration or environment variables.
+/// A specification consists of a kind, optionally followed by a colon and a
+/// comma-separated list of parameters:
+///
+/// ```text
+/// exponential:base=10ms,factor=2,max=5s,attempts=8,jitter
+/// ```
+///
+/// The supported kinds and their parameters are:
+///
+/// - `fixed` with `base`, the interval between attempts (default `100ms`).
+/// - `linear` with `base`, the initial delay (default `100ms`),
+///   and `increment`, the amount added after each attempt (default `base`).
+/// - `exponential` with `base`, the initial delay (default `100ms`),
+///   and `factor`, the multiplier applied after each attempt (default `2`).
+/// - `fibonacci` with `base`, the initial delay (default `100ms`).
+/// - `immediate`, retrying without any delay.
+///
+/// Every kind additionally accepts `max`, a cap for the delays, `attempts`,
+/// the maximum number of attempts including the first one, and the `jitter` flag.
+/// Durations are given as a number followed by one of the units `ns`, `us`, `ms`, `s`, `m` or `h`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tokio_retry::strategy::StrategySpec;
+///
+/// let strategy: StrategySpec = "exponential:base=10ms,factor=3,attempts=3".parse().unwrap();
+///
+/// assert_eq!(strategy.collect::<Vec<_>>(), vec![Duration::from_millis(10), Duration::from_millis(30)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StrategySpec {
+    kind: Kind,
+    max_delay: Option<Duration>,
+    retries: Option<usize>,
+    remaining: Option<usize>,
+    jitter: bool
+}
+
+#[derive(Clone, Debug)]
+enum Kind {
+    Fixed(FixedInterval),
+    Linear(LinearBackoff),
+    Exponential(ExponentialBackoff),
+    Fibonacci(FibonacciBackoff),
+    Immediate(NoDelay)
+}
+
+impl Iterator for StrategySpec {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if let Some(ref mut remaining) = self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+
+        let mut duration = match self.kind {
+            Kind::Fixed(ref mut s) => s.next(),
+            Kind::Linear(ref mut s) => s.next(),
+            Kind::Exponential(ref mut s) => s.next(),
+            Kind::Fibonacci(ref mut s) => s.next(),
+            Kind::Immediate(ref mut s) => s.next()
+        }?;
+
+        if let Some(max_delay) = self.max_delay {
+            duration = cmp::min(duration, max_delay);
+        }
+        if self.jitter {
+            duration = jitter(duration);
+        }
+
+        Some(duration)
+    }
+}
+
+impl FromStr for StrategySpec {
+    type Err = ParseStrategyError;
+
+    fn from_str(spec: &str) -> Result<StrategySpec, ParseStrategyError> {
+        let mut parts = spec.trim().splitn(2, ':');
+        let kind = parts.next().unwrap_or("").trim();
+
+        let mut base = Duration::from_millis(100);
+        let mut increment = None;
+        let mut factor = 2.0;
+        let mut max_delay = None;
+        let mut remaining = None;
+        let mut jitter = false;
+
+        for param in parts.next().unwrap_or("").split(',').map(str::trim).filter(|param| !param.is_empty()) {
+            let mut param = param.splitn(2, '=');
+            let key = param.next().unwrap_or("").trim();
+            let value = param.next().map(str::trim);
+
+            match (key, value) {
+                ("jitter", None) => jitter = true,
+                ("base", Some(value)) => base = parse_duration(value)?,
+                ("increment", Some(value)) => increment = Some(parse_duration(value)?),
+                ("max", Some(value)) => max_delay = Some(parse_duration(value)?),
+                ("factor", Some(value)) => factor = value.parse()
+                    .map_err(|_| ParseStrategyError::new(format!("invalid factor `{}`", value)))?,
+                ("attempts", Some(value)) => remaining = Some(value.parse::<usize>()
+                    .map_err(|_| ParseStrategyError::new(format!("invalid number of attempts `{}`", value)))?
+                    .saturating_sub(1)),
+                _ => return Err(ParseStrategyError::new(format!("invalid parameter `{}`", key)))
+            }
+        }
+
+        let kind = match kind {
+            "fixed" => Kind::Fixed(FixedInterval::new(base)),
+            "linear" => Kind::Linear(LinearBackoff::new(base, increment.unwrap_or(base))),
+            "exponential" => Kind::Exponential(ExponentialBackoff::from_duration(base).factor(factor)),
+            "fibonacci" => Kind::Fibonacci(FibonacciBackoff::from_duration(base)),
+            "immediate" => Kind::Immediate(NoDelay),
+            _ => return Err(ParseStrategyError::new(format!("unknown strategy `{}`", kind)))
+        };
+
+        Ok(StrategySpec{kind, max_delay, retries: remaining, remaining, jitter})
+    }
+}
+
+fn parse_duration(value: &str) -> Result<Duration, ParseStrategyError> {
+    let split = value.find(|c: char| c.is_alphabetic()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let scale = match unit {
+        "ns" => 1e-9,
+        "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => return Err(ParseStrategyError::new(format!("invalid duration `{}`", value)))
+    };
+
+    number.parse::<f64>().ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * scale).ok())
+        .ok_or_else(|| ParseStrategyError::new(format!("invalid duration `{}`", value)))
+}
+
+/// An error which can be returned when parsing a [`StrategySpec`](struct.StrategySpec.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseStrategyError {
+    message: String
+}
+
+impl ParseStrategyError {
+    fn new(message: String) -> ParseStrategyError {
+        ParseStrategyError{message}
+    }
+}
+
+impl fmt::Display for ParseStrategyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.message.fmt(formatter)
+    }
+}
+
+impl Error for ParseStrategyError {}
+
+impl ResettableStrategy for StrategySpec {
+    fn reset(&mut self) {
+        match self.kind {
+            Kind::Fixed(ref mut s) => s.reset(),
+            Kind::Linear(ref mut s) => s.reset(),
+            Kind::Exponential(ref mut s) => s.reset(),
+            Kind::Fibonacci(ref mut s) => s.reset(),
+            Kind::Immediate(ref mut s) => s.reset()
+        }
+        self.remaining = self.retries;
+    }
+}
+
+#[test]
+fn parses_exponential_with_all_parameters() {
+    let s: StrategySpec = "exponential:base=10ms,factor=2,max=50ms,attempts=5".parse().unwrap();
+
+    assert_eq!(s.collect::<Vec<_>>(), vec![
+        Duration::from_millis(10),
+        Duration::from_millis(20),
+        Duration::from_millis(40),
+        Duration::from_millis(50)
+    ]);
+}
+
+#[test]
+fn parses_kinds_with_defaults() {
+    let mut fixed: StrategySpec = "fixed".parse().unwrap();
+    let mut linear: StrategySpec = "linear:base=1s,increment=500ms".parse().unwrap();
+    let mut immediate: StrategySpec = " immediate : attempts=2 ".parse().unwrap();
+
+    assert_eq!(fixed.next(), Some(Duration::from_millis(100)));
+    assert_eq!(linear.nth(2), Some(Duration::from_secs(2)));
+    assert_eq!(immediate.next(), Some(Duration::ZERO));
+    assert_eq!(immediate.next(), None);
+}
+
+#[test]
+fn jitters_within_cap() {
+    let s: StrategySpec = "fibonacci:base=10ms,max=1s,jitter,attempts=20".parse().unwrap();
+
+    for duration in s {
+        assert!(duration <= Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn rejects_invalid_specs() {
+    assert!("quadratic".parse::<StrategySpec>().is_err());
+    assert!("fixed:base=10 parsecs".parse::<StrategySpec>().is_err());
+    assert!("fixed:base".parse::<StrategySpec>().is_err());
+    assert!("exponential:factor=fast".parse::<StrategySpec>().is_err());
+    assert!("exponential:speed=1".parse::<StrategySpec>().is_err());
+}
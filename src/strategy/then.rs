@@ -0,0 +1,110 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// A retry strategy adapter which switches to a second strategy once the first one is exhausted.
+///
+/// As it forwards `succeeded` and `attempts_exhausted` to the current tier, this is a
+/// `BackoffStrategy` rather than an `Iterator`. Iterator adapters such as `jittered` or
+/// `capped` therefore need to be applied to the tiers, before combining them.
+///
+/// This `struct` is created by the [`then`](trait.StrategyExt.html#method.then)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+    switched: bool
+}
+
+impl<A, B> Then<A, B> {
+    pub(crate) fn new(first: A, second: B) -> Then<A, B> {
+        Then{first, second, switched: false}
+    }
+}
+
+impl<E, A: BackoffStrategy<E>, B: BackoffStrategy<E>> BackoffStrategy<E> for Then<A, B> {
+    fn delay(&mut self, attempt: usize, error: &E) -> Option<Duration> {
+        if !self.switched {
+            match self.first.delay(attempt, error) {
+                Some(duration) => return Some(duration),
+                None => self.switched = true
+            }
+        }
+        self.second.delay(attempt, error)
+    }
+
+    fn succeeded(&mut self) {
+        if self.switched {
+            self.second.succeeded()
+        } else {
+            self.first.succeeded()
+        }
+    }
+
+    fn attempts_exhausted(&self) -> bool {
+        self.switched && self.second.attempts_exhausted()
+    }
+}
+
+impl<A, B> IntoStrategy for Then<A, B> {
+    type Strategy = Then<A, B>;
+
+    fn into_strategy(self) -> Then<A, B> {
+        self
+    }
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for Then<A, B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "then({}, {})", self.first, self.second)
+    }
+}
+
+/// Resetting rewinds both strategies, and starts over with the first one.
+impl<A: ResettableStrategy, B: ResettableStrategy> ResettableStrategy for Then<A, B> {
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+        self.switched = false;
+    }
+}
+
+#[test]
+fn switches_to_the_second_strategy_once_the_first_is_exhausted() {
+    use super::{ExponentialBackoff, FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(10)).take(2).then(ExponentialBackoff::from_millis(100));
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 3, &()), Some(Duration::from_millis(100)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 4, &()), Some(Duration::from_millis(10000)));
+}
+
+#[test]
+fn rewinds_to_the_first_strategy_on_reset() {
+    use super::{ExplicitSchedule, StrategyExt};
+    let mut s = ExplicitSchedule::new(vec![Duration::from_millis(10)]).then(ExplicitSchedule::new(vec![Duration::from_millis(100)]));
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(100)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 3, &()), None);
+
+    s.reset();
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(100)));
+    assert_eq!(s.to_string(), "then(explicit([10ms]), explicit([100ms]))");
+}
+
+#[test]
+fn composes_tiers_built_from_iterator_adapters() {
+    use super::{ExponentialBackoff, FixedInterval, StrategyExt};
+    let max = Duration::from_millis(500);
+    let mut s = FixedInterval::new(Duration::from_millis(10)).take(1).capped(max).then(ExponentialBackoff::from_millis(100).capped(max));
+
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 1, &()), Some(Duration::from_millis(10)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 2, &()), Some(Duration::from_millis(100)));
+    assert_eq!(BackoffStrategy::<()>::delay(&mut s, 3, &()), Some(max));
+}
@@ -0,0 +1,71 @@
+use std::cmp;
+use std::fmt;
+use std::time::Duration;
+use std::iter::Iterator;
+
+use super::{saturating_mul, DefaultRng, JitterRng, ResettableStrategy};
+
+/// A retry strategy driven by truncated binary exponential back-off.
+///
+/// After the `n`-th failed attempt, the delay is a random number of slot times
+/// between `0` and `2^min(n, cap) - 1`, as used for collision resolution in Ethernet.
+#[derive(Clone, Debug)]
+pub struct TruncatedBinaryBackoff {
+    slot: Duration,
+    cap: u32,
+    attempt: u32
+}
+
+impl TruncatedBinaryBackoff {
+    /// Constructs a new truncated binary exponential back-off strategy,
+    /// given the slot time and the attempt after which the range stops growing.
+    pub fn new(slot: Duration, cap: u32) -> TruncatedBinaryBackoff {
+        TruncatedBinaryBackoff{slot, cap: cmp::min(cap, 63), attempt: 0}
+    }
+}
+
+impl Iterator for TruncatedBinaryBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.attempt = cmp::min(self.attempt + 1, self.cap);
+
+        let slots = 1u64 << self.attempt;
+        let chosen = cmp::min((DefaultRng.next_unit() * slots as f64) as u64, slots - 1);
+
+        Some(saturating_mul(self.slot, chosen as f64))
+    }
+}
+
+impl fmt::Display for TruncatedBinaryBackoff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "truncated_binary(slot={:?}, cap={})", self.slot, self.cap)
+    }
+}
+
+impl ResettableStrategy for TruncatedBinaryBackoff {
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[test]
+fn stays_within_the_growing_range() {
+    let slot = Duration::from_millis(10);
+    let mut s = TruncatedBinaryBackoff::new(slot, 10);
+
+    for attempt in 1..8 {
+        let duration = s.next().unwrap();
+        assert!(duration <= slot * ((1 << attempt) - 1));
+    }
+}
+
+#[test]
+fn stops_growing_at_cap() {
+    let slot = Duration::from_millis(10);
+    let mut s = TruncatedBinaryBackoff::new(slot, 2);
+
+    for _ in 0..100 {
+        assert!(s.next().unwrap() <= slot * 3);
+    }
+}
@@ -0,0 +1,72 @@
+use std::cmp;
+use std::fmt;
+use std::time::{Duration, Instant};
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+/// A retry strategy adapter which never schedules a retry beyond a deadline.
+///
+/// Delays which would end after the deadline are shortened to end right at it,
+/// and no further delays are yielded once the deadline has passed.
+///
+/// This `struct` is created by the [`until_deadline`](trait.StrategyExt.html#method.until_deadline)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct UntilDeadline<S> {
+    inner: S,
+    deadline: Instant
+}
+
+impl<S> UntilDeadline<S> {
+    pub(crate) fn new(inner: S, deadline: Instant) -> UntilDeadline<S> {
+        UntilDeadline{inner, deadline}
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for UntilDeadline<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+
+        if now >= self.deadline {
+            return None;
+        }
+
+        let duration = self.inner.next()?;
+        Some(cmp::min(duration, self.deadline - now))
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for UntilDeadline<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        write!(formatter, "until_deadline({}, remaining={:?})", self.inner, remaining)
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for UntilDeadline<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn truncates_delays_at_the_deadline() {
+    use super::{FixedInterval, StrategyExt};
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let mut s = FixedInterval::new(Duration::from_secs(3600)).until_deadline(deadline);
+
+    let delay = s.next().unwrap();
+    assert!(delay <= Duration::from_secs(60));
+    assert!(delay > Duration::from_secs(50));
+}
+
+#[test]
+fn stops_once_the_deadline_has_passed() {
+    use super::{FixedInterval, StrategyExt};
+    let mut s = FixedInterval::new(Duration::from_millis(10)).until_deadline(Instant::now());
+
+    assert_eq!(s.next(), None);
+}
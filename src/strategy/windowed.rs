@@ -0,0 +1,137 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::iter::Iterator;
+
+use super::ResettableStrategy;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// A daily wall-clock window, in UTC.
+///
+/// Windows ending before they start wrap around midnight, so that
+/// e.g. a window from 04:00 to 02:00 excludes a nightly maintenance window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeWindow {
+    start: u64,
+    end: u64
+}
+
+impl TimeWindow {
+    /// Constructs a new window from `start` to `end`, both given as time since midnight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or `end` is not within a day, or if they are equal.
+    pub fn new(start: Duration, end: Duration) -> TimeWindow {
+        let (start, end) = (start.as_secs(), end.as_secs());
+        assert!(start < DAY && end < DAY, "TimeWindow: start and end must be within a day");
+        assert!(start != end, "TimeWindow: start and end must differ");
+        TimeWindow{start, end}
+    }
+
+    fn contains(&self, time_of_day: u64) -> bool {
+        if self.start < self.end {
+            self.start <= time_of_day && time_of_day < self.end
+        } else {
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+
+    fn wait_until_open(&self, time_of_day: u64) -> u64 {
+        (self.start + DAY - time_of_day) % DAY
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "{:02}:{:02}-{:02}:{:02}", self.start / 3600, self.start / 60 % 60, self.end / 3600, self.end / 60 % 60)
+    }
+}
+
+/// A retry strategy adapter which only permits retries within wall-clock windows.
+///
+/// Delays which would end outside of every window are extended until the
+/// next window opens.
+///
+/// This `struct` is created by the [`within_windows`](trait.StrategyExt.html#method.within_windows)
+/// method on [`StrategyExt`](trait.StrategyExt.html).
+#[derive(Clone, Debug)]
+pub struct Windowed<S> {
+    inner: S,
+    windows: Vec<TimeWindow>
+}
+
+impl<S> Windowed<S> {
+    pub(crate) fn new(inner: S, windows: Vec<TimeWindow>) -> Windowed<S> {
+        assert!(!windows.is_empty(), "Windowed: at least one window is required");
+        Windowed{inner, windows}
+    }
+
+    fn delay_at(&self, now: Duration, duration: Duration) -> Duration {
+        let time_of_day = now.checked_add(duration).map_or(0, |at| at.as_secs() % DAY);
+
+        if self.windows.iter().any(|window| window.contains(time_of_day)) {
+            return duration;
+        }
+
+        let wait = self.windows.iter().map(|window| window.wait_until_open(time_of_day)).min().unwrap_or(0);
+        duration.saturating_add(Duration::from_secs(wait))
+    }
+}
+
+impl<S: Iterator<Item=Duration>> Iterator for Windowed<S> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.inner.next()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+        Some(self.delay_at(now, duration))
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Windowed<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "within_windows({}, windows=[", self.inner)?;
+        for (i, window) in self.windows.iter().enumerate() {
+            if i > 0 {
+                write!(formatter, ", ")?;
+            }
+            write!(formatter, "{}", window)?;
+        }
+        write!(formatter, "])")
+    }
+}
+
+impl<S: ResettableStrategy> ResettableStrategy for Windowed<S> {
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[test]
+fn keeps_delays_ending_within_a_window() {
+    use super::NoDelay;
+    let s = Windowed::new(NoDelay, vec![TimeWindow::new(Duration::from_secs(9 * 3600), Duration::from_secs(17 * 3600))]);
+    let now = Duration::from_secs(10 * 3600);
+
+    assert_eq!(s.delay_at(now, Duration::from_secs(60)), Duration::from_secs(60));
+}
+
+#[test]
+fn extends_delays_until_the_next_window_opens() {
+    use super::NoDelay;
+    let s = Windowed::new(NoDelay, vec![TimeWindow::new(Duration::from_secs(9 * 3600), Duration::from_secs(17 * 3600))]);
+    let now = Duration::from_secs(3 * DAY + 18 * 3600);
+
+    assert_eq!(s.delay_at(now, Duration::from_secs(60)), Duration::from_secs(15 * 3600));
+}
+
+#[test]
+fn wraps_windows_around_midnight() {
+    use super::NoDelay;
+    let s = Windowed::new(NoDelay, vec![TimeWindow::new(Duration::from_secs(4 * 3600), Duration::from_secs(2 * 3600))]);
+
+    assert_eq!(s.delay_at(Duration::from_secs(3600), Duration::from_secs(60)), Duration::from_secs(60));
+    assert_eq!(s.delay_at(Duration::from_secs(3 * 3600), Duration::ZERO), Duration::from_secs(3600));
+    assert_eq!(format!("{}", s), "within_windows(no_delay, windows=[04:00-02:00])");
+}
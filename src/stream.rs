@@ -0,0 +1,252 @@
+use futures::{task, Async, Future, Poll, Stream};
+use std::cmp;
+use std::mem;
+use std::time::Instant;
+
+use super::{RetryError, Sleep};
+use super::future::DEFAULT_YIELD_AFTER;
+use super::strategy::{BackoffStrategy, IntoStrategy, ResettableStrategy};
+
+/// Builds the inner streams of a [`RetryStream`](struct.RetryStream.html).
+///
+/// This is implemented by closures returning streams, and by the factories of checkpointed
+/// retry streams, see `RetryStream::checkpointed`.
+pub trait StreamFactory<St: Stream> {
+    /// Builds a new inner stream.
+    fn build(&mut self) -> St;
+
+    /// Observes an item yielded by the current inner stream.
+    fn yielded(&mut self, _item: &St::Item) {}
+}
+
+impl<St: Stream, F: FnMut() -> St> StreamFactory<St> for F {
+    fn build(&mut self) -> St {
+        self()
+    }
+}
+
+/// A stream factory which resumes from the checkpoint of the last item yielded.
+///
+/// This `struct` is created by `RetryStream::checkpointed`.
+pub struct Checkpointed<C, F, K> {
+    checkpoint: C,
+    factory: F,
+    last: Option<K>
+}
+
+impl<St, C, F, K> StreamFactory<St> for Checkpointed<C, F, K>
+    where St: Stream, C: FnMut(&St::Item) -> K, F: FnMut(Option<&K>) -> St {
+    fn build(&mut self) -> St {
+        (self.factory)(self.last.as_ref())
+    }
+
+    fn yielded(&mut self, item: &St::Item) {
+        self.last = Some((self.checkpoint)(item));
+    }
+}
+
+enum StreamState<S: Sleep, St> {
+    Pending,
+    Streaming(St),
+    Sleeping(S::Future),
+    Done
+}
+
+/// Stream that yields the items of an inner stream, and rebuilds the inner stream via
+/// a factory whenever it fails, after the delay of the strategy.
+///
+/// Item delivery resumes transparently with the rebuilt stream, so this suits flaky
+/// upstream subscriptions which can be re-established at will. The strategy is reset
+/// once a rebuilt stream yields an item, so that only consecutive failures back off.
+/// Streams which can resume from an offset should be built via `RetryStream::checkpointed`.
+///
+/// The stream ends with its inner stream, or fails with `RetryError::RetriesExhausted`,
+/// and then ends, should the strategy give up.
+pub struct RetryStream<S: Sleep, I, F, St> {
+    sleep: S,
+    strategy: I,
+    factory: F,
+    yield_after: usize,
+    attempt: usize,
+    started: Instant,
+    state: StreamState<S, St>
+}
+
+impl<S, I, F, St> RetryStream<S, I, F, St>
+    where S: Sleep, I: BackoffStrategy<St::Error> + ResettableStrategy, F: StreamFactory<St>, St: Stream {
+    /// Constructs a retry stream, building the inner stream via the factory.
+    pub fn new<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, factory: F) -> RetryStream<S, I, F, St> {
+        RetryStream{
+            sleep,
+            strategy: strategy.into_strategy(),
+            factory,
+            yield_after: DEFAULT_YIELD_AFTER,
+            attempt: 0,
+            started: Instant::now(),
+            state: StreamState::Pending
+        }
+    }
+
+    /// Yields to the executor after the given number of consecutive state transitions
+    /// within a single poll, which defaults to 64, see `RetryFuture::yield_after`.
+    pub fn yield_after(mut self, transitions: usize) -> RetryStream<S, I, F, St> {
+        self.yield_after = cmp::max(transitions, 1);
+        self
+    }
+
+    /// Returns the number of consecutive failures of the inner stream so far.
+    pub fn failures(&self) -> usize {
+        self.attempt
+    }
+}
+
+impl<S, I, C, F, K, St> RetryStream<S, I, Checkpointed<C, F, K>, St>
+    where S: Sleep, I: BackoffStrategy<St::Error> + ResettableStrategy, C: FnMut(&St::Item) -> K, F: FnMut(Option<&K>) -> St, St: Stream {
+    /// Like `new`, but records a checkpoint, such as an offset or event id, for every item
+    /// yielded, and passes the last one to the factory, so that rebuilt streams resume where
+    /// the failed one left off instead of replaying it from the start.
+    ///
+    /// The factory is passed `None` until the first item has been yielded.
+    pub fn checkpointed<T: IntoStrategy<Strategy=I>>(sleep: S, strategy: T, checkpoint: C, factory: F) -> RetryStream<S, I, Checkpointed<C, F, K>, St> {
+        RetryStream::new(sleep, strategy, Checkpointed{checkpoint, factory, last: None})
+    }
+
+    /// Returns the checkpoint of the last item yielded, if any.
+    pub fn last_checkpoint(&self) -> Option<&K> {
+        self.factory.last.as_ref()
+    }
+}
+
+impl<S, I, F, St> Stream for RetryStream<S, I, F, St>
+    where S: Sleep, I: BackoffStrategy<St::Error> + ResettableStrategy, F: StreamFactory<St>, St: Stream {
+    type Item = St::Item;
+    type Error = RetryError<St::Error, <S::Future as Future>::Error>;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        for _ in 0..self.yield_after {
+            self.state = match mem::replace(&mut self.state, StreamState::Done) {
+                StreamState::Pending => StreamState::Streaming(self.factory.build()),
+                StreamState::Streaming(mut stream) => match stream.poll() {
+                    Ok(Async::NotReady) => {
+                        self.state = StreamState::Streaming(stream);
+                        return Ok(Async::NotReady);
+                    },
+                    Ok(Async::Ready(Some(item))) => {
+                        self.factory.yielded(&item);
+                        if self.attempt > 0 {
+                            self.strategy.reset();
+                            self.attempt = 0;
+                        }
+                        self.state = StreamState::Streaming(stream);
+                        return Ok(Async::Ready(Some(item)));
+                    },
+                    Ok(Async::Ready(None)) => return Ok(Async::Ready(None)),
+                    Err(err) => {
+                        if self.attempt == 0 {
+                            self.started = Instant::now();
+                        }
+                        self.attempt += 1;
+                        match self.strategy.delay(self.attempt, &err) {
+                            Some(delay) => StreamState::Sleeping(self.sleep.sleep(delay)),
+                            None => return Err(RetryError::RetriesExhausted{
+                                last_error: err,
+                                attempts: self.attempt,
+                                total_elapsed: self.started.elapsed(),
+                                recent_errors: Vec::new(),
+                                attempts_exhausted: self.strategy.attempts_exhausted()
+                            })
+                        }
+                    }
+                },
+                StreamState::Sleeping(mut future) => match future.poll().map_err(RetryError::TimerError)? {
+                    Async::NotReady => {
+                        self.state = StreamState::Sleeping(future);
+                        return Ok(Async::NotReady);
+                    },
+                    Async::Ready(_) => StreamState::Pending
+                },
+                StreamState::Done => return Ok(Async::Ready(None))
+            };
+        }
+
+        task::current().notify();
+        Ok(Async::NotReady)
+    }
+}
+
+#[test]
+fn resumes_items_from_rebuilt_streams() {
+    use futures::stream::{iter_result, IterResult};
+    use std::vec::IntoIter;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let mut builds = 0;
+    let stream = RetryStream::new(tokio_timer::Timer::default(), NoDelay.max_attempts(3), || -> IterResult<IntoIter<Result<u64, &str>>> {
+        builds += 1;
+        match builds {
+            1 => iter_result(vec![Ok(1), Err("disconnected")]),
+            2 => iter_result(vec![Err("refused")]),
+            3 => iter_result(vec![Ok(2), Err("disconnected")]),
+            _ => iter_result(vec![Ok(3)])
+        }
+    });
+
+    assert_eq!(stream.collect().wait(), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn fails_once_consecutive_failures_are_exhausted() {
+    use futures::stream::{iter_result, IterResult};
+    use std::vec::IntoIter;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let mut stream = RetryStream::new(tokio_timer::Timer::default(), NoDelay.max_attempts(2), || -> IterResult<IntoIter<Result<u64, &str>>> {
+        iter_result(vec![Err("refused")])
+    }).wait();
+
+    match stream.next() {
+        Some(Err(RetryError::RetriesExhausted{last_error, attempts, ..})) => {
+            assert_eq!(last_error, "refused");
+            assert_eq!(attempts, 2);
+        },
+        _ => panic!("retries were not exhausted")
+    }
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn resumes_rebuilt_streams_from_the_last_checkpoint() {
+    use futures::stream::{iter_result, IterResult};
+    use std::vec::IntoIter;
+    use super::strategy::{NoDelay, StrategyExt};
+
+    let mut offsets = Vec::new();
+    let stream = RetryStream::checkpointed(tokio_timer::Timer::default(), NoDelay.max_attempts(2), |&(offset, _): &(u64, char)| offset, |last: Option<&u64>| -> IterResult<IntoIter<Result<(u64, char), ()>>> {
+        offsets.push(last.cloned());
+        match last {
+            None => iter_result(vec![Ok((1, 'a')), Ok((2, 'b')), Err(())]),
+            Some(_) => iter_result(vec![Ok((3, 'c'))])
+        }
+    });
+
+    assert_eq!(stream.map(|(_, item)| item).collect().wait(), Ok(vec!['a', 'b', 'c']));
+    assert_eq!(offsets, vec![None, Some(2)]);
+}
+
+#[test]
+fn yields_between_immediate_rebuilds() {
+    use futures::future::lazy;
+    use futures::stream::{once, Once};
+    use super::strategy::NoDelay;
+
+    let mut builds = 0;
+    let mut stream = RetryStream::new(tokio_timer::Timer::default(), NoDelay, || -> Once<(), u64> {
+        builds += 1;
+        once(Err(builds))
+    }).yield_after(4);
+
+    let polled = lazy(|| Ok::<_, ()>(stream.poll())).wait();
+    assert_eq!(polled, Ok(Ok(Async::NotReady)));
+    drop(stream);
+    assert_eq!(builds, 2);
+}
@@ -0,0 +1,118 @@
+use futures::{Async, Future, Poll};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryFuture};
+use super::condition::RetryContext;
+
+/// Represents how a supervised future terminated.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Exit<T, E> {
+    /// The future completed, although it was meant to run indefinitely.
+    Completed(T),
+    /// The future failed.
+    Failed(E)
+}
+
+impl<T, E: fmt::Display> fmt::Display for Exit<T, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Exit::Completed(_) => write!(formatter, "supervised future completed"),
+            Exit::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<T: fmt::Debug, E: Error> Error for Exit<T, E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            Exit::Completed(_) => None,
+            Exit::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// An action adapter for long-running futures, such as daemons or actors, which treats
+/// any termination as a failure, so that they are restarted by the retry strategy.
+///
+/// This `struct` is created by `RetryFuture::respawn`.
+pub struct Supervised<A> {
+    action: A
+}
+
+impl<A> Supervised<A> {
+    pub(crate) fn new(action: A) -> Supervised<A> {
+        Supervised{action}
+    }
+}
+
+impl<A: Action> Action for Supervised<A> {
+    type Item = Infallible;
+    type Error = Exit<A::Item, A::Error>;
+    type Future = SupervisedFuture<A::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        SupervisedFuture{inner: self.action.run()}
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        let inner = match *previous {
+            Exit::Completed(_) => self.action.run(),
+            Exit::Failed(ref err) => self.action.run_after(err)
+        };
+        SupervisedFuture{inner}
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            Exit::Failed(ref err) => Some(err),
+            _ => None
+        });
+        SupervisedFuture{inner: self.action.run_with_context(previous, context)}
+    }
+}
+
+/// Future of a single run of a [`Supervised`](struct.Supervised.html) action.
+pub struct SupervisedFuture<F> {
+    inner: F
+}
+
+impl<F: Future> Future for SupervisedFuture<F> {
+    type Item = Infallible;
+    type Error = Exit<F::Item, F::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(item)) => Err(Exit::Completed(item)),
+            Err(err) => Err(Exit::Failed(err))
+        }
+    }
+}
+
+/// Future that restarts a long-running future whenever it terminates, see `RetryFuture::respawn`.
+pub type Supervisor<S, I, A> = RetryFuture<S, I, Supervised<A>>;
+
+#[test]
+fn restarts_completed_and_failed_futures() {
+    use super::RetryError;
+    use super::strategy::NoDelay;
+
+    let mut runs = 0;
+    let res = RetryFuture::respawn(tokio_timer::Timer::default(), NoDelay.take(2), || {
+        runs += 1;
+        if runs % 2 == 0 { Err(runs) } else { Ok(runs) }
+    }).wait();
+
+    match res {
+        Err(RetryError::RetriesExhausted{last_error, attempts, ..}) => {
+            assert_eq!(last_error, Exit::Completed(3));
+            assert_eq!(attempts, 3);
+        },
+        _ => panic!("restarts were not exhausted")
+    }
+}
@@ -0,0 +1,395 @@
+//! Instrumentation of retry sequences via the optional `tracing`, `otel`, `log` and `metrics` integrations.
+//!
+//! Without the respective features enabled, all of this compiles down to nothing.
+
+use std::time::Duration;
+#[cfg(feature = "log")]
+use std::fmt;
+
+#[cfg(feature = "log")]
+use log;
+#[cfg(feature = "metrics")]
+use metrics;
+#[cfg(feature = "tracing")]
+use tracing;
+
+/// The target of log records, unless configured otherwise.
+#[cfg(feature = "log")]
+pub(crate) const DEFAULT_LOG_TARGET: &str = "tokio_retry";
+
+/// The `policy` label of metrics for unnamed retry sequences.
+#[cfg(feature = "metrics")]
+pub(crate) const DEFAULT_NAME: &str = "default";
+
+/// Prefixes log messages with the name of the retry sequence, if any.
+#[cfg(feature = "log")]
+struct Prefix(Option<&'static str>);
+
+#[cfg(feature = "log")]
+impl fmt::Display for Prefix {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.0 {
+            Some(name) => write!(formatter, "{}: ", name),
+            None => Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) type Entered = tracing::span::EnteredSpan;
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Entered;
+
+/// Instruments a single retry sequence.
+pub(crate) struct Telemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    attempt_span: tracing::Span,
+    #[cfg(feature = "otel")]
+    operation: tracing::Span,
+    #[cfg(feature = "log")]
+    log_target: &'static str,
+    name: Option<&'static str>
+}
+
+impl Telemetry {
+    pub(crate) fn new() -> Telemetry {
+        Telemetry {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!("retry", name = tracing::field::Empty),
+            #[cfg(feature = "tracing")]
+            attempt_span: tracing::Span::none(),
+            #[cfg(feature = "otel")]
+            operation: tracing::Span::current(),
+            #[cfg(feature = "log")]
+            log_target: DEFAULT_LOG_TARGET,
+            name: None
+        }
+    }
+
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    pub(crate) fn set_name(&mut self, name: &'static str) {
+        #[cfg(feature = "tracing")]
+        self.span.record("name", name);
+        self.name = Some(name);
+    }
+
+    #[cfg(feature = "metrics")]
+    fn policy(&self) -> &'static str {
+        self.name.unwrap_or(DEFAULT_NAME)
+    }
+
+    #[cfg(feature = "log")]
+    pub(crate) fn set_log_target(&mut self, target: &'static str) {
+        self.log_target = target;
+    }
+
+    /// Enters the span of the whole retry sequence.
+    pub(crate) fn enter(&self) -> Entered {
+        #[cfg(feature = "tracing")]
+        {
+            self.span.clone().entered()
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            Entered
+        }
+    }
+
+    /// Enters the span of the current attempt.
+    pub(crate) fn enter_attempt(&self) -> Entered {
+        #[cfg(feature = "tracing")]
+        {
+            self.attempt_span.clone().entered()
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            Entered
+        }
+    }
+
+    /// Starts the span of a new attempt, which was preceded by the given delay.
+    pub(crate) fn attempt_started(&mut self, attempt: usize, delay: Duration) {
+        #[cfg(all(feature = "tracing", not(feature = "otel")))]
+        {
+            self.attempt_span = tracing::debug_span!(parent: &self.span, "attempt", attempt);
+        }
+        #[cfg(feature = "otel")]
+        {
+            self.attempt_span = tracing::debug_span!(
+                parent: &self.span,
+                "attempt",
+                attempt,
+                retry.count = attempt - 1,
+                retry.delay = delay.as_millis() as u64
+            );
+            self.attempt_span.follows_from(&self.operation);
+        }
+        let _ = (attempt, delay);
+    }
+
+    pub(crate) fn attempt_failed(&self, attempt: usize, elapsed: Duration) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.attempt_span, attempt, elapsed = ?elapsed, "attempt failed");
+        #[cfg(feature = "log")]
+        log::debug!(target: self.log_target, "{}attempt {} failed after {:?}", Prefix(self.name), attempt, elapsed);
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("retry_attempts_total", "policy" => self.policy()).increment(1);
+            metrics::histogram!("retry_attempt_duration_seconds", "policy" => self.policy(), "outcome" => "failure").record(elapsed);
+        }
+        let _ = (attempt, elapsed);
+    }
+
+    pub(crate) fn retrying(&self, attempt: usize, delay: Duration) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, attempt, delay = ?delay, "retrying");
+        #[cfg(feature = "log")]
+        log::warn!(target: self.log_target, "{}retrying attempt {} in {:?}", Prefix(self.name), attempt, delay);
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("retry_retries_total", "policy" => self.policy()).increment(1);
+            metrics::histogram!("retry_sleep_duration_seconds", "policy" => self.policy()).record(delay);
+        }
+        let _ = (attempt, delay);
+    }
+
+    pub(crate) fn succeeded(&self, attempt: usize, elapsed: Duration) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, attempt, "succeeded");
+        #[cfg(feature = "log")]
+        {
+            if attempt > 1 {
+                log::debug!(target: self.log_target, "{}succeeded after {} attempts", Prefix(self.name), attempt);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("retry_attempts_total", "policy" => self.policy()).increment(1);
+            metrics::histogram!("retry_attempt_duration_seconds", "policy" => self.policy(), "outcome" => "success").record(elapsed);
+        }
+        let _ = (attempt, elapsed);
+    }
+
+    pub(crate) fn gave_up(&self, attempt: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, attempt, "giving up");
+        #[cfg(feature = "log")]
+        log::debug!(target: self.log_target, "{}giving up after {} attempts, error is not retried", Prefix(self.name), attempt);
+        let _ = attempt;
+    }
+
+    pub(crate) fn rejected(&self, attempt: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, attempt, "retry rejected");
+        #[cfg(feature = "log")]
+        log::debug!(target: self.log_target, "{}giving up after {} attempts, retry is rejected by the limiter", Prefix(self.name), attempt);
+        let _ = attempt;
+    }
+
+    pub(crate) fn exhausted(&self, attempt: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(parent: &self.span, attempt, "retries exhausted");
+        #[cfg(feature = "log")]
+        log::error!(target: self.log_target, "{}retries exhausted after {} attempts", Prefix(self.name), attempt);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("retry_exhausted_total", "policy" => self.policy()).increment(1);
+        let _ = attempt;
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn instruments_attempts_with_spans() {
+    use futures::Future;
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tracing::{Event, Id, Metadata, Subscriber};
+    use tracing::span::{Attributes, Record};
+    use tokio_timer::Timer;
+    use super::RetryFuture;
+    use super::strategy::FixedInterval;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<&'static str>>>,
+        events: Arc<AtomicUsize>
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool { true }
+        fn new_span(&self, attrs: &Attributes) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(attrs.metadata().name());
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event) { self.events.fetch_add(1, Ordering::SeqCst); }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    let recorder = Recorder::default();
+    let mut calls = 0;
+    let res = tracing::subscriber::with_default(recorder.clone(), || {
+        RetryFuture::spawn(Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(2), || {
+            calls += 1;
+            if calls < 3 { Err(()) } else { Ok(calls) }
+        }).wait()
+    });
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(*recorder.spans.lock().unwrap(), vec!["retry", "attempt", "attempt", "attempt"]);
+    // two failed attempts, two retries and the final success
+    assert_eq!(recorder.events.load(Ordering::SeqCst), 5);
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn logs_retries_to_the_configured_target() {
+    use futures::Future;
+    use std::sync::Mutex;
+    use log::{Level, Log, Metadata, Record};
+    use tokio_timer::Timer;
+    use super::RetryFuture;
+    use super::strategy::FixedInterval;
+
+    struct Recorder(Mutex<Vec<(Level, String)>>);
+
+    impl Log for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool { true }
+        fn log(&self, record: &Record) {
+            if record.target() == "retry_test" {
+                self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+    let _ = log::set_logger(&RECORDER);
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let res = RetryFuture::spawn_named(Timer::default(), "test", FixedInterval::new(Duration::from_millis(1)).take(1), || Err::<(), ()>(()))
+        .log_target("retry_test")
+        .wait();
+
+    assert!(res.is_err());
+    let records = RECORDER.0.lock().unwrap();
+    let levels: Vec<_> = records.iter().map(|&(level, _)| level).collect();
+    assert_eq!(levels, vec![Level::Debug, Level::Warn, Level::Debug, Level::Error]);
+    assert_eq!(records[1].1, "test: retrying attempt 1 in 1ms");
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn reports_metrics_labeled_by_name() {
+    use futures::Future;
+    use std::sync::Mutex;
+    use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    use tokio_timer::Timer;
+    use super::RetryFuture;
+    use super::strategy::FixedInterval;
+
+    #[derive(Default)]
+    struct Registrations(Mutex<Vec<String>>);
+
+    impl Registrations {
+        fn push(&self, key: &Key) {
+            let policy = key.labels().find(|label| label.key() == "policy").map(|label| label.value().to_owned());
+            self.0.lock().unwrap().push(format!("{}:{}", key.name(), policy.unwrap_or_default()));
+        }
+    }
+
+    impl Recorder for Registrations {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, key: &Key, _: &Metadata) -> Counter { self.push(key); Counter::noop() }
+        fn register_gauge(&self, key: &Key, _: &Metadata) -> Gauge { self.push(key); Gauge::noop() }
+        fn register_histogram(&self, key: &Key, _: &Metadata) -> Histogram { self.push(key); Histogram::noop() }
+    }
+
+    let recorder = Registrations::default();
+    let res = metrics::with_local_recorder(&recorder, || {
+        RetryFuture::spawn(Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(1), || Err::<(), ()>(()))
+            .named("test")
+            .wait()
+    });
+
+    assert!(res.is_err());
+    assert_eq!(*recorder.0.lock().unwrap(), vec![
+        "retry_attempts_total:test",
+        "retry_attempt_duration_seconds:test",
+        "retry_retries_total:test",
+        "retry_sleep_duration_seconds:test",
+        "retry_attempts_total:test",
+        "retry_attempt_duration_seconds:test",
+        "retry_exhausted_total:test"
+    ]);
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn links_attempts_to_the_operation() {
+    use futures::Future;
+    use std::sync::{Arc, Mutex};
+    use tracing::{Event, Id, Metadata, Subscriber};
+    use tracing::span::{Attributes, Record};
+    use tracing_core::span::Current;
+    use tokio_timer::Timer;
+    use super::RetryFuture;
+    use super::strategy::FixedInterval;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        metadata: Arc<Mutex<Vec<&'static Metadata<'static>>>>,
+        entered: Arc<Mutex<Vec<u64>>>,
+        links: Arc<Mutex<Vec<(u64, u64)>>>
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool { true }
+        fn new_span(&self, attrs: &Attributes) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            let fields: Vec<_> = attrs.metadata().fields().iter().map(|field| field.name()).collect();
+            spans.push(format!("{}({})", attrs.metadata().name(), fields.join(",")));
+            self.metadata.lock().unwrap().push(attrs.metadata());
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record) {}
+        fn record_follows_from(&self, span: &Id, follows: &Id) {
+            self.links.lock().unwrap().push((span.into_u64(), follows.into_u64()));
+        }
+        fn event(&self, _: &Event) {}
+        fn enter(&self, span: &Id) { self.entered.lock().unwrap().push(span.into_u64()); }
+        fn exit(&self, _: &Id) { self.entered.lock().unwrap().pop(); }
+        fn current_span(&self) -> Current {
+            match self.entered.lock().unwrap().last() {
+                Some(&id) => Current::new(Id::from_u64(id), self.metadata.lock().unwrap()[id as usize - 1]),
+                None => Current::none()
+            }
+        }
+    }
+
+    let recorder = Recorder::default();
+    let res = tracing::subscriber::with_default(recorder.clone(), || {
+        let _operation = tracing::info_span!("operation").entered();
+        RetryFuture::spawn(Timer::default(), FixedInterval::new(Duration::from_millis(1)).take(1), || Err::<(), ()>(())).wait()
+    });
+
+    assert!(res.is_err());
+    assert_eq!(*recorder.spans.lock().unwrap(), vec![
+        "operation()",
+        "retry(name)",
+        "attempt(attempt,retry.count,retry.delay)",
+        "attempt(attempt,retry.count,retry.delay)"
+    ]);
+    assert_eq!(*recorder.links.lock().unwrap(), vec![(3, 1), (4, 1)]);
+}
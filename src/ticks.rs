@@ -0,0 +1,56 @@
+use futures::{Async, Future, Poll, Stream};
+use std::time::Duration;
+
+use super::Sleep;
+
+/// Stream that yields once after each delay of a retry strategy.
+///
+/// Every item is the delay that has just elapsed, and the stream ends once the
+/// strategy is exhausted. This allows driving custom retry or polling loops with
+/// the same schedules as `RetryFuture`.
+///
+/// This `struct` is created by the [`into_stream`](strategy/trait.StrategyExt.html#method.into_stream)
+/// method on [`StrategyExt`](strategy/trait.StrategyExt.html).
+pub struct Ticks<S, I> where S: Sleep {
+    sleep: S,
+    strategy: I,
+    current: Option<(Duration, S::Future)>
+}
+
+impl<S: Sleep, I> Ticks<S, I> {
+    pub(crate) fn new(sleep: S, strategy: I) -> Ticks<S, I> {
+        Ticks{sleep, strategy, current: None}
+    }
+}
+
+impl<S: Sleep, I: Iterator<Item=Duration>> Stream for Ticks<S, I> {
+    type Item = Duration;
+    type Error = <S::Future as Future>::Error;
+
+    fn poll(&mut self) -> Poll<Option<Duration>, Self::Error> {
+        let (duration, mut future) = match self.current.take() {
+            Some(current) => current,
+            None => match self.strategy.next() {
+                None => return Ok(Async::Ready(None)),
+                Some(duration) => (duration, self.sleep.sleep(duration))
+            }
+        };
+
+        match future.poll()? {
+            Async::NotReady => {
+                self.current = Some((duration, future));
+                Ok(Async::NotReady)
+            },
+            Async::Ready(_) => Ok(Async::Ready(Some(duration)))
+        }
+    }
+}
+
+#[test]
+fn yields_after_each_delay() {
+    use std::default::Default;
+    use super::strategy::{FixedInterval, StrategyExt};
+    let ticks = FixedInterval::new(Duration::from_millis(1)).take(3).into_stream(tokio_timer::Timer::default());
+
+    assert_eq!(ticks.collect().wait(), Ok(vec![Duration::from_millis(1); 3]));
+}
@@ -0,0 +1,126 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Action, RetryableError, Sleep};
+use super::condition::RetryContext;
+use super::expiry::Expiry;
+
+/// Represents the errors of an action whose attempts are bounded by a timeout.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum TimeoutError<E, TE> {
+    /// The attempt did not complete within the given timeout.
+    TimedOut(Duration),
+    /// Sleeping until the timeout failed, e.g. because the timeout exceeds the `max_timeout`
+    /// of the timer, so that the attempt was aborted rather than left unbounded.
+    TimerError(TE),
+    /// The attempt failed.
+    Failed(E)
+}
+
+impl<E: fmt::Display, TE: fmt::Display> fmt::Display for TimeoutError<E, TE> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            TimeoutError::TimedOut(timeout) => write!(formatter, "attempt timed out after {:?}", timeout),
+            TimeoutError::TimerError(ref err) => write!(formatter, "sleeping until the timeout failed: {}", err),
+            TimeoutError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error, TE: Error> Error for TimeoutError<E, TE> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            TimeoutError::TimedOut(_) => None,
+            TimeoutError::TimerError(ref err) => Some(err),
+            TimeoutError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Timeouts are always retryable, timer errors never, as the timer is unlikely to accept
+/// the same timeout later; other errors only if they report to be.
+impl<E: RetryableError, TE> RetryableError for TimeoutError<E, TE> {
+    fn is_retryable(&self) -> bool {
+        match *self {
+            TimeoutError::TimedOut(_) => true,
+            TimeoutError::TimerError(_) => false,
+            TimeoutError::Failed(ref err) => err.is_retryable()
+        }
+    }
+}
+
+/// An action adapter which fails attempts with `TimeoutError::TimedOut`
+/// once they take longer than a timeout, or with `TimeoutError::TimerError`
+/// should sleeping until the timeout fail.
+///
+/// This `struct` is created by `RetryFuture::spawn_with_timeout`.
+pub struct Timeout<A, S> {
+    action: A,
+    sleep: S,
+    timeout: Duration
+}
+
+impl<A, S> Timeout<A, S> {
+    pub(crate) fn new(action: A, sleep: S, timeout: Duration) -> Timeout<A, S> {
+        Timeout{action, sleep, timeout}
+    }
+}
+
+impl<A: Action, S: Sleep> Timeout<A, S> {
+    fn bound(&mut self, inner: A::Future) -> TimeoutFuture<A::Future, S::Future> {
+        TimeoutFuture{inner, timer: Expiry::new(self.sleep.sleep(self.timeout)), timeout: self.timeout}
+    }
+}
+
+impl<A: Action, S: Sleep> Action for Timeout<A, S> {
+    type Item = A::Item;
+    type Error = TimeoutError<A::Error, <S::Future as Future>::Error>;
+    type Future = TimeoutFuture<A::Future, S::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        let inner = self.action.run();
+        self.bound(inner)
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        let inner = match *previous {
+            TimeoutError::TimedOut(_) | TimeoutError::TimerError(_) => self.action.run(),
+            TimeoutError::Failed(ref err) => self.action.run_after(err)
+        };
+        self.bound(inner)
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            TimeoutError::Failed(ref err) => Some(err),
+            _ => None
+        });
+        let inner = self.action.run_with_context(previous, context);
+        self.bound(inner)
+    }
+}
+
+/// Future of a single attempt at a [`Timeout`](struct.Timeout.html) action.
+pub struct TimeoutFuture<F, T> {
+    inner: F,
+    timer: Expiry<T>,
+    timeout: Duration
+}
+
+impl<F: Future, T: Future> Future for TimeoutFuture<F, T> {
+    type Item = F::Item;
+    type Error = TimeoutError<F::Error, T::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.timer.race(&mut self.inner, TimeoutError::Failed, TimeoutError::TimerError)? {
+            Async::Ready(Some(item)) => Ok(Async::Ready(item)),
+            Async::Ready(None) => Err(TimeoutError::TimedOut(self.timeout)),
+            Async::NotReady => Ok(Async::NotReady)
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use futures::{Async, Future, Poll};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::Action;
+use super::condition::RetryContext;
+
+/// Represents the errors of an action retried until its result satisfies a predicate.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum UntilError<T, E> {
+    /// The action succeeded, but its result did not satisfy the predicate.
+    Unsatisfied(T),
+    /// The action failed.
+    Failed(E)
+}
+
+impl<T, E: fmt::Display> fmt::Display for UntilError<T, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            UntilError::Unsatisfied(_) => write!(formatter, "result did not satisfy the predicate"),
+            UntilError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<T: fmt::Debug, E: Error> Error for UntilError<T, E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            UntilError::Unsatisfied(_) => None,
+            UntilError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// An action adapter which fails with `UntilError::Unsatisfied` for results
+/// that do not satisfy a predicate.
+///
+/// This `struct` is created by `RetryFuture::spawn_until`.
+pub struct Until<A, P> {
+    action: A,
+    predicate: Arc<P>
+}
+
+impl<A, P> Until<A, P> {
+    pub(crate) fn new(action: A, predicate: P) -> Until<A, P> {
+        Until{action, predicate: Arc::new(predicate)}
+    }
+}
+
+impl<A: Action, P: Fn(&A::Item) -> bool> Action for Until<A, P> {
+    type Item = A::Item;
+    type Error = UntilError<A::Item, A::Error>;
+    type Future = UntilFuture<A::Future, P>;
+
+    fn run(&mut self) -> Self::Future {
+        UntilFuture{inner: self.action.run(), predicate: self.predicate.clone()}
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        let inner = match *previous {
+            UntilError::Unsatisfied(_) => self.action.run(),
+            UntilError::Failed(ref err) => self.action.run_after(err)
+        };
+        UntilFuture{inner, predicate: self.predicate.clone()}
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            UntilError::Failed(ref err) => Some(err),
+            _ => None
+        });
+        UntilFuture{inner: self.action.run_with_context(previous, context), predicate: self.predicate.clone()}
+    }
+}
+
+/// Future of a single attempt at an [`Until`](struct.Until.html) action.
+pub struct UntilFuture<F, P> {
+    inner: F,
+    predicate: Arc<P>
+}
+
+impl<F: Future, P: Fn(&F::Item) -> bool> Future for UntilFuture<F, P> {
+    type Item = F::Item;
+    type Error = UntilError<F::Item, F::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(item)) => if (self.predicate)(&item) {
+                Ok(Async::Ready(item))
+            } else {
+                Err(UntilError::Unsatisfied(item))
+            },
+            Err(err) => Err(UntilError::Failed(err))
+        }
+    }
+}
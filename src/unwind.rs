@@ -0,0 +1,157 @@
+use futures::{Future, Poll};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::{Action, RetryableError};
+use super::condition::RetryContext;
+
+/// Represents the errors of an action whose panics are caught.
+#[derive(Debug)]
+pub enum UnwindError<E> {
+    /// The attempt panicked, with the given payload.
+    Panicked(Box<dyn Any + Send>),
+    /// The attempt failed.
+    Failed(E)
+}
+
+impl<E> UnwindError<E> {
+    /// Returns the message of the panic, if the attempt panicked with one.
+    pub fn panic_message(&self) -> Option<&str> {
+        match *self {
+            UnwindError::Panicked(ref payload) => payload.downcast_ref::<&str>().cloned()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str)),
+            UnwindError::Failed(_) => None
+        }
+    }
+
+    /// Returns the error of a failed attempt, or else re-raises its panic.
+    ///
+    /// This allows panics to be retried, and yet to propagate once retries are exhausted,
+    /// e.g. via `RetryError::map_operation_error(UnwindError::resume_unwind)`.
+    pub fn resume_unwind(self) -> E {
+        match self {
+            UnwindError::Panicked(payload) => panic::resume_unwind(payload),
+            UnwindError::Failed(err) => err
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for UnwindError<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            UnwindError::Panicked(_) => match self.panic_message() {
+                Some(message) => write!(formatter, "attempt panicked: {}", message),
+                None => write!(formatter, "attempt panicked")
+            },
+            UnwindError::Failed(ref err) => err.fmt(formatter)
+        }
+    }
+}
+
+impl<E: Error> Error for UnwindError<E> {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            UnwindError::Panicked(_) => None,
+            UnwindError::Failed(ref err) => Some(err)
+        }
+    }
+}
+
+/// Panics are always retryable, other errors only if they report to be.
+impl<E: RetryableError> RetryableError for UnwindError<E> {
+    fn is_retryable(&self) -> bool {
+        match *self {
+            UnwindError::Panicked(_) => true,
+            UnwindError::Failed(ref err) => err.is_retryable()
+        }
+    }
+}
+
+/// An action adapter which catches panics of attempts, failing them with
+/// `UnwindError::Panicked` instead, so that they are retried like any other failure.
+///
+/// This `struct` is created by `RetryFuture::spawn_catch_unwind`.
+pub struct CatchUnwind<A> {
+    action: A
+}
+
+impl<A> CatchUnwind<A> {
+    pub(crate) fn new(action: A) -> CatchUnwind<A> {
+        CatchUnwind{action}
+    }
+}
+
+impl<A: Action> CatchUnwind<A> {
+    fn catch<R: FnOnce(&mut A) -> A::Future>(&mut self, run: R) -> CatchUnwindFuture<A::Future> {
+        let action = &mut self.action;
+        match panic::catch_unwind(AssertUnwindSafe(|| run(action))) {
+            Ok(inner) => CatchUnwindFuture{inner: Some(inner), panic: None},
+            Err(payload) => CatchUnwindFuture{inner: None, panic: Some(payload)}
+        }
+    }
+}
+
+impl<A: Action> Action for CatchUnwind<A> {
+    type Item = A::Item;
+    type Error = UnwindError<A::Error>;
+    type Future = CatchUnwindFuture<A::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        self.catch(|action| action.run())
+    }
+
+    fn run_after(&mut self, previous: &Self::Error) -> Self::Future {
+        match *previous {
+            UnwindError::Panicked(_) => self.catch(|action| action.run()),
+            UnwindError::Failed(ref err) => self.catch(|action| action.run_after(err))
+        }
+    }
+
+    fn run_with_context(&mut self, previous: Option<&Self::Error>, context: &RetryContext) -> Self::Future {
+        let previous = previous.and_then(|previous| match *previous {
+            UnwindError::Failed(ref err) => Some(err),
+            _ => None
+        });
+        self.catch(|action| action.run_with_context(previous, context))
+    }
+}
+
+/// Future of a single attempt at a [`CatchUnwind`](struct.CatchUnwind.html) action.
+pub struct CatchUnwindFuture<F> {
+    inner: Option<F>,
+    panic: Option<Box<dyn Any + Send>>
+}
+
+impl<F: Future> Future for CatchUnwindFuture<F> {
+    type Item = F::Item;
+    type Error = UnwindError<F::Error>;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if let Some(payload) = self.panic.take() {
+            return Err(UnwindError::Panicked(payload));
+        }
+        let inner = self.inner.as_mut().expect("polled CatchUnwindFuture after completion");
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.poll())) {
+            Ok(polled) => polled.map_err(UnwindError::Failed),
+            Err(payload) => {
+                self.inner = None;
+                Err(UnwindError::Panicked(payload))
+            }
+        }
+    }
+}
+
+#[test]
+fn fails_attempts_which_panic() {
+    use futures::future::{lazy, FutureResult};
+
+    let mut action = CatchUnwind::new(|| -> FutureResult<u64, ()> { panic!("boom") });
+    let err = action.run().wait().err().unwrap();
+    assert_eq!(err.panic_message(), Some("boom"));
+
+    let mut action = CatchUnwind::new(|| lazy(|| -> Result<u64, ()> { panic!("later") }));
+    let err = action.run().wait().err().unwrap();
+    assert_eq!(err.panic_message(), Some("later"));
+}
@@ -0,0 +1,170 @@
+use futures::{Async, Poll, Stream};
+use futures::task::{self, Task};
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// How a `RetryFuture` finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryOutcome {
+    /// An attempt succeeded.
+    Succeeded,
+    /// The retry sequence failed, e.g. because its retries were exhausted.
+    Failed,
+    /// The `RetryFuture` was dropped before it completed.
+    Dropped
+}
+
+/// A snapshot of the state of a `RetryFuture`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RetryStatus {
+    attempt: usize,
+    next_attempt: Option<Instant>,
+    outcome: Option<RetryOutcome>
+}
+
+impl RetryStatus {
+    /// Returns the number of the current or most recent attempt, counting from 1.
+    ///
+    /// This is 0 before the initial attempt has been started.
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    /// Returns when the next attempt is scheduled, while waiting for it.
+    pub fn next_attempt(&self) -> Option<Instant> {
+        self.next_attempt
+    }
+
+    /// Returns whether the `RetryFuture` is waiting for the next attempt.
+    pub fn is_sleeping(&self) -> bool {
+        self.next_attempt.is_some()
+    }
+
+    /// Returns the time remaining until the next attempt, while waiting for it.
+    pub fn next_delay_hint(&self) -> Option<Duration> {
+        self.next_attempt.map(|next_attempt| next_attempt.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns how the `RetryFuture` finished, once it did.
+    pub fn outcome(&self) -> Option<RetryOutcome> {
+        self.outcome
+    }
+
+    /// Returns whether the `RetryFuture` has finished, i.e. the status no longer changes.
+    pub fn is_finished(&self) -> bool {
+        self.outcome.is_some()
+    }
+}
+
+struct Shared {
+    status: RetryStatus,
+    version: usize,
+    waiters: Vec<Task>
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Receiver of the live state of a `RetryFuture`.
+///
+/// This allows e.g. long-running reconnect loops to report "retrying, next attempt in 32s"
+/// from a status endpoint while the retry future is driven elsewhere. The current state
+/// can be read at any time via `status`, while the watch, as a `Stream`, yields every
+/// state it has not seen yet, and ends after the state in which the future finished.
+///
+/// This `struct` is created by the `watch` method on `RetryFuture`.
+#[derive(Clone)]
+pub struct RetryWatch {
+    shared: Arc<Mutex<Shared>>,
+    seen: usize
+}
+
+impl RetryWatch {
+    /// Returns the current state of the watched `RetryFuture`.
+    pub fn status(&self) -> RetryStatus {
+        lock(&self.shared).status
+    }
+}
+
+impl fmt::Debug for RetryWatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.debug_struct("RetryWatch")
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+/// Yields the state whenever it changed since it was last yielded, starting with the current one.
+/// Changes in between polls are coalesced into the latest state.
+impl Stream for RetryWatch {
+    type Item = RetryStatus;
+    type Error = Infallible;
+
+    fn poll(&mut self) -> Poll<Option<RetryStatus>, Infallible> {
+        let mut shared = lock(&self.shared);
+        if shared.version != self.seen {
+            self.seen = shared.version;
+            return Ok(Async::Ready(Some(shared.status)));
+        }
+        if shared.status.is_finished() {
+            return Ok(Async::Ready(None));
+        }
+        if !shared.waiters.iter().any(Task::will_notify_current) {
+            shared.waiters.push(task::current());
+        }
+        Ok(Async::NotReady)
+    }
+}
+
+/// The sending half of a `RetryWatch`, as held by the watched `RetryFuture`.
+///
+/// Dropping it before the future finished publishes `RetryOutcome::Dropped`.
+pub(crate) struct StatusSender {
+    shared: Arc<Mutex<Shared>>
+}
+
+impl StatusSender {
+    pub(crate) fn new(attempt: usize) -> StatusSender {
+        let status = RetryStatus{attempt, next_attempt: None, outcome: None};
+        StatusSender{shared: Arc::new(Mutex::new(Shared{status, version: 1, waiters: Vec::new()}))}
+    }
+
+    pub(crate) fn subscribe(&self) -> RetryWatch {
+        RetryWatch{shared: self.shared.clone(), seen: 0}
+    }
+
+    pub(crate) fn update(&self, attempt: usize, next_attempt: Option<Instant>) {
+        self.publish(|status| *status = RetryStatus{attempt, next_attempt, outcome: None});
+    }
+
+    pub(crate) fn finish(&self, outcome: RetryOutcome) {
+        self.publish(|status| {
+            status.next_attempt = None;
+            status.outcome = Some(outcome);
+        });
+    }
+
+    fn publish<F: FnOnce(&mut RetryStatus)>(&self, f: F) {
+        let waiters = {
+            let mut shared = lock(&self.shared);
+            if shared.status.is_finished() {
+                return;
+            }
+            f(&mut shared.status);
+            shared.version += 1;
+            shared.waiters.split_off(0)
+        };
+        for waiter in waiters {
+            waiter.notify();
+        }
+    }
+}
+
+impl Drop for StatusSender {
+    fn drop(&mut self) {
+        self.finish(RetryOutcome::Dropped);
+    }
+}